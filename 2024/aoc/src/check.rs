@@ -0,0 +1,500 @@
+//! Input signatures, so a wrong-day, truncated or mangled `input.txt` is caught before a parser
+//! silently accepts it.
+//!
+//! Each day declares the shape of its input in `puzzle/input.sig`, as `key = value` lines like an
+//! example sidecar. Sections are separated by blank lines. A plain key applies to every section
+//! and `key.N` to section N only.
+//!
+//! ```text
+//! # Ordering rules, then updates
+//! sections = 2
+//! line.1 = \d+\|\d+
+//! line.2 = \d+(,\d+)*
+//! ```
+//!
+//! - `sections`: how many sections there are, or `many` for any number of alike sections.
+//! - `line`: a regex each whole line must match.
+//! - `chars`: the only characters a line may contain.
+//! - `lines`: how many lines the section has.
+//! - `grid`: `true` if every line in the section is the same length.
+
+use std::{collections::BTreeMap, fmt, fs, path::Path};
+
+use regex::Regex;
+use util::{
+    crypt::{self, Key},
+    input::{self, INPUT_FILE},
+};
+
+use crate::day::Day;
+
+pub const SIGNATURE_FILE: &str = "input.sig";
+const SHOWN_ERRORS: usize = 5;
+
+#[derive(Debug, PartialEq)]
+pub enum SignatureErr {
+    Io(String),
+    BadLine(usize),
+    UnknownKey(String),
+    BadValue(String, String),
+}
+
+#[derive(Debug, Clone)]
+struct LinePattern {
+    source: String,
+    regex: Regex,
+}
+
+#[derive(Debug, Clone, Default)]
+struct SectionShape {
+    line: Option<LinePattern>,
+    chars: Option<String>,
+    lines: Option<usize>,
+    grid: Option<bool>,
+}
+
+impl SectionShape {
+    fn or(&self, fallback: &SectionShape) -> SectionShape {
+        SectionShape {
+            line: self.line.clone().or_else(|| fallback.line.clone()),
+            chars: self.chars.clone().or_else(|| fallback.chars.clone()),
+            lines: self.lines.or(fallback.lines),
+            grid: self.grid.or(fallback.grid),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Sections {
+    Exactly(usize),
+    Many,
+}
+
+#[derive(Debug, Clone)]
+pub struct Signature {
+    sections: Sections,
+    every: SectionShape,
+    numbered: BTreeMap<usize, SectionShape>,
+}
+
+/// Something wrong with an input. Warnings are about the file's formatting and don't stop the
+/// input matching its signature.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Finding {
+    Crlf,
+    NoTrailingNewline,
+    TrailingBlankLines(usize),
+    Empty,
+    SectionCount {
+        expected: usize,
+        found: usize,
+    },
+    LineCount {
+        section: usize,
+        expected: usize,
+        found: usize,
+    },
+    BadChar {
+        line: usize,
+        found: char,
+    },
+    BadLine {
+        line: usize,
+        pattern: String,
+    },
+    Ragged {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl Finding {
+    pub fn is_warning(&self) -> bool {
+        matches!(
+            self,
+            Finding::Crlf | Finding::NoTrailingNewline | Finding::TrailingBlankLines(_)
+        )
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Finding::Crlf => write!(f, "uses CRLF line endings"),
+            Finding::NoTrailingNewline => write!(f, "does not end with a newline"),
+            Finding::TrailingBlankLines(n) => write!(f, "ends with {} blank line(s)", n),
+            Finding::Empty => write!(f, "is empty"),
+            Finding::SectionCount { expected, found } => {
+                write!(f, "has {} section(s), expected {}", found, expected)
+            }
+            Finding::LineCount {
+                section,
+                expected,
+                found,
+            } => write!(
+                f,
+                "section {} has {} line(s), expected {}",
+                section, found, expected
+            ),
+            Finding::BadChar { line, found } => write!(f, "line {}: unexpected {:?}", line, found),
+            Finding::BadLine { line, pattern } => {
+                write!(f, "line {}: does not match `{}`", line, pattern)
+            }
+            Finding::Ragged {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}: {} characters wide, expected {}",
+                line, found, expected
+            ),
+        }
+    }
+}
+
+fn bad_value(key: &str, value: &str) -> SignatureErr {
+    SignatureErr::BadValue(key.to_string(), value.to_string())
+}
+
+impl Signature {
+    pub fn parse(text: &str) -> Result<Signature, SignatureErr> {
+        let mut signature = Signature {
+            sections: Sections::Exactly(1),
+            every: SectionShape::default(),
+            numbered: BTreeMap::new(),
+        };
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or(SignatureErr::BadLine(idx + 1))?;
+            let (key, value) = (key.trim(), value.trim());
+            if key == "sections" {
+                signature.sections = match value {
+                    "many" => Sections::Many,
+                    _ => Sections::Exactly(value.parse().map_err(|_| bad_value(key, value))?),
+                };
+                continue;
+            }
+
+            let (name, shape) = match key.split_once('.') {
+                Some((name, section)) => {
+                    let section = section.parse().map_err(|_| bad_value(key, value))?;
+                    (name, signature.numbered.entry(section).or_default())
+                }
+                None => (key, &mut signature.every),
+            };
+            match name {
+                "line" => {
+                    let regex = Regex::new(&format!("^(?:{})$", value))
+                        .map_err(|_| bad_value(key, value))?;
+                    shape.line = Some(LinePattern {
+                        source: value.to_string(),
+                        regex,
+                    });
+                }
+                "chars" => shape.chars = Some(value.to_string()),
+                "lines" => shape.lines = Some(value.parse().map_err(|_| bad_value(key, value))?),
+                "grid" => shape.grid = Some(value.parse().map_err(|_| bad_value(key, value))?),
+                _ => return Err(SignatureErr::UnknownKey(key.to_string())),
+            }
+        }
+        Ok(signature)
+    }
+
+    pub fn load(path: &Path) -> Result<Signature, SignatureErr> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| SignatureErr::Io(format!("{}: {}", path.display(), e)))?;
+        Signature::parse(&text)
+    }
+
+    fn shape(&self, section: usize) -> SectionShape {
+        match self.numbered.get(&section) {
+            Some(shape) => shape.or(&self.every),
+            None => self.every.clone(),
+        }
+    }
+
+    /// Everything wrong with `input`, warnings first.
+    pub fn check(&self, input: &str) -> Vec<Finding> {
+        let mut findings = vec![];
+        if input.contains('\r') {
+            findings.push(Finding::Crlf);
+        }
+        let input = input.replace("\r\n", "\n");
+        if input.trim().is_empty() {
+            findings.push(Finding::Empty);
+            return findings;
+        }
+        if !input.ends_with('\n') {
+            findings.push(Finding::NoTrailingNewline);
+        }
+        let content = input.trim_end_matches('\n');
+        let blank_lines = input.len() - content.len();
+        if blank_lines > 1 {
+            findings.push(Finding::TrailingBlankLines(blank_lines - 1));
+        }
+
+        let mut sections: Vec<Vec<(usize, &str)>> = vec![vec![]];
+        for (idx, line) in content.lines().enumerate() {
+            if line.is_empty() {
+                sections.push(vec![]);
+            } else if let Some(section) = sections.last_mut() {
+                section.push((idx + 1, line));
+            }
+        }
+        if let Sections::Exactly(expected) = self.sections {
+            if sections.len() != expected {
+                findings.push(Finding::SectionCount {
+                    expected,
+                    found: sections.len(),
+                });
+            }
+        }
+
+        for (idx, lines) in sections.iter().enumerate() {
+            let shape = self.shape(idx + 1);
+            if let Some(expected) = shape.lines.filter(|&n| n != lines.len()) {
+                findings.push(Finding::LineCount {
+                    section: idx + 1,
+                    expected,
+                    found: lines.len(),
+                });
+            }
+            let width = lines.first().map_or(0, |(_, line)| line.len());
+            for &(line_no, line) in lines {
+                let bad_char = shape
+                    .chars
+                    .as_ref()
+                    .and_then(|chars| line.chars().find(|c| !chars.contains(*c)));
+                if let Some(found) = bad_char {
+                    findings.push(Finding::BadChar {
+                        line: line_no,
+                        found,
+                    });
+                } else if let Some(pattern) =
+                    shape.line.as_ref().filter(|p| !p.regex.is_match(line))
+                {
+                    findings.push(Finding::BadLine {
+                        line: line_no,
+                        pattern: pattern.source.clone(),
+                    });
+                } else if shape.grid == Some(true) && line.len() != width {
+                    findings.push(Finding::Ragged {
+                        line: line_no,
+                        expected: width,
+                        found: line.len(),
+                    });
+                }
+            }
+        }
+        findings
+    }
+
+    /// Whether `input` has the right shape, ignoring formatting warnings.
+    pub fn accepts(&self, input: &str) -> bool {
+        self.check(input).iter().all(|f| f.is_warning())
+    }
+}
+
+/// Every day that declares a signature, for suggesting where a wrong input came from.
+fn all_signatures(days: &[Day]) -> Vec<(&Day, Signature)> {
+    days.iter()
+        .filter_map(|day| {
+            let signature = Signature::load(&day.puzzle_dir().join(SIGNATURE_FILE)).ok()?;
+            Some((day, signature))
+        })
+        .collect()
+}
+
+/// Checks `input` against its day's signature, returning the lines to report and whether it
+/// passed. If it fails, any other days it would pass for are suggested.
+fn report(day: &Day, input: &str, signatures: &[(&Day, Signature)]) -> (Vec<String>, bool) {
+    let Some((_, signature)) = signatures.iter().find(|(d, _)| d.name == day.name) else {
+        return (vec!["no signature".to_string()], true);
+    };
+    let findings = signature.check(input);
+    let (warnings, errors): (Vec<_>, Vec<_>) = findings.iter().partition(|f| f.is_warning());
+    let mut lines: Vec<_> = warnings.iter().map(|w| format!("warning: {}", w)).collect();
+    lines.extend(
+        errors
+            .iter()
+            .take(SHOWN_ERRORS)
+            .map(|e| format!("error: {}", e)),
+    );
+    if errors.len() > SHOWN_ERRORS {
+        lines.push(format!("... and {} more", errors.len() - SHOWN_ERRORS));
+    }
+    if errors.is_empty() {
+        return (lines, true);
+    }
+
+    let matches: Vec<_> = signatures
+        .iter()
+        .filter(|(d, s)| d.name != day.name && s.accepts(input))
+        .map(|(d, _)| d.name.as_str())
+        .collect();
+    match matches.as_slice() {
+        [] => lines.push("does not look like any day's input".to_string()),
+        _ => lines.push(format!("looks like input for {}", matches.join(" or "))),
+    }
+    (lines, false)
+}
+
+/// Checks the `input.txt` of each day in `days`, printing what is wrong with each. Returns
+/// whether they all matched their signatures.
+pub fn check(days: &[Day], all_days: &[Day]) -> bool {
+    let signatures = all_signatures(all_days);
+    let mut passed = true;
+    for day in days {
+        let path = day.puzzle_dir().join(INPUT_FILE);
+        let encrypted_only = !path.exists() && crypt::encrypted_path(&path).exists();
+        let (lines, ok) = if encrypted_only && Key::find().is_none() {
+            (vec!["skipped: encrypted and no key".to_string()], true)
+        } else {
+            match input::open(&path).and_then(input::read_to_string) {
+                Ok(input) => report(day, &input, &signatures),
+                Err(err) => (vec![format!("error: {}", err)], false),
+            }
+        };
+        passed &= ok;
+        match lines.as_slice() {
+            [] => println!("{}: ok", day.name),
+            _ => {
+                println!("{}:", day.name);
+                lines.iter().for_each(|line| println!("  {}", line));
+            }
+        }
+    }
+    passed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day::year_dir;
+
+    const ORDERING: &str = "
+        # Ordering rules, then updates
+        sections = 2
+        line.1 = \\d+\\|\\d+
+        line.2 = \\d+(,\\d+)*
+    ";
+
+    #[test]
+    fn test_parse() {
+        let signature = Signature::parse(ORDERING).unwrap();
+        assert_eq!(signature.sections, Sections::Exactly(2));
+        assert!(signature.every.line.is_none());
+        assert_eq!(signature.shape(2).line.unwrap().source, "\\d+(,\\d+)*");
+        assert_eq!(
+            Signature::parse("width = 3").unwrap_err(),
+            SignatureErr::UnknownKey("width".to_string())
+        );
+        assert_eq!(
+            Signature::parse("line = (").unwrap_err(),
+            SignatureErr::BadValue("line".to_string(), "(".to_string())
+        );
+        assert_eq!(
+            Signature::parse("grid").unwrap_err(),
+            SignatureErr::BadLine(1)
+        );
+    }
+
+    #[test]
+    fn test_check_sections() {
+        let signature = Signature::parse(ORDERING).unwrap();
+        assert_eq!(signature.check("1|2\n3|4\n\n1,2\n"), vec![]);
+        assert_eq!(
+            signature.check("1|2\n3|4\n"),
+            vec![Finding::SectionCount {
+                expected: 2,
+                found: 1
+            }]
+        );
+        assert_eq!(
+            signature.check("1|2\n\n1,2\n3,\n"),
+            vec![Finding::BadLine {
+                line: 4,
+                pattern: "\\d+(,\\d+)*".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_check_grid() {
+        let signature =
+            Signature::parse("sections = many\nchars = #.\ngrid = true\nlines = 2").unwrap();
+        assert_eq!(signature.check("#.\n..\n\n##\n.#\n"), vec![]);
+        assert_eq!(
+            signature.check("#.\n.\n\n##\n"),
+            vec![
+                Finding::Ragged {
+                    line: 2,
+                    expected: 2,
+                    found: 1
+                },
+                Finding::LineCount {
+                    section: 2,
+                    expected: 2,
+                    found: 1
+                },
+            ]
+        );
+        assert_eq!(
+            signature.check("#.\n.O\n"),
+            vec![Finding::BadChar {
+                line: 2,
+                found: 'O'
+            }]
+        );
+    }
+
+    #[test]
+    fn test_check_formatting() {
+        let signature = Signature::parse("line = \\d+").unwrap();
+        let findings = signature.check("1\r\n2\r\n");
+        assert_eq!(findings, vec![Finding::Crlf]);
+        assert!(signature.accepts("1\r\n2\r\n"));
+        assert_eq!(signature.check("1\n2"), vec![Finding::NoTrailingNewline]);
+        assert_eq!(
+            signature.check("1\n2\n\n\n"),
+            vec![Finding::TrailingBlankLines(2)]
+        );
+        assert_eq!(signature.check("\n"), vec![Finding::Empty]);
+    }
+
+    #[test]
+    fn test_every_input_matches_its_signature() {
+        let days = Day::all(&year_dir());
+        assert_eq!(days.len(), 25);
+        let signatures = all_signatures(&days);
+        assert_eq!(signatures.len(), days.len());
+        for (day, signature) in &signatures {
+            let Some(input) = input::real_input(&day.dir) else {
+                continue;
+            };
+            assert_eq!(signature.check(&input), vec![], "{}", day.name);
+        }
+    }
+
+    #[test]
+    fn test_suggests_the_right_day() {
+        let days = Day::all(&year_dir());
+        let signatures = all_signatures(&days);
+        let Some(day05) = input::real_input(&days[4].dir) else {
+            return;
+        };
+        let (lines, passed) = report(&days[6], &day05, &signatures);
+        assert!(!passed);
+        assert_eq!(lines.last().unwrap(), "looks like input for day05");
+
+        let truncated = &day05[..day05.len() - 3];
+        let (lines, passed) = report(&days[4], truncated, &signatures);
+        assert!(!passed);
+        assert_eq!(lines[0], "warning: does not end with a newline");
+    }
+}
@@ -0,0 +1,93 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, PartialEq)]
+pub enum DayErr {
+    BadName(String),
+    NotFound(PathBuf),
+}
+
+/// One day's crate, such as `day16` in the year directory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Day {
+    pub name: String,
+    pub dir: PathBuf,
+}
+
+impl Day {
+    /// Finds a day by number or crate name, so `6`, `06` and `day06` are all the same day.
+    pub fn find(year_dir: &Path, name: &str) -> Result<Day, DayErr> {
+        let number: u32 = name
+            .trim_start_matches("day")
+            .parse()
+            .map_err(|_| DayErr::BadName(name.to_string()))?;
+        if !(1..=25).contains(&number) {
+            return Err(DayErr::BadName(name.to_string()));
+        }
+        let name = format!("day{:02}", number);
+        let dir = year_dir.join(&name);
+        if !dir.join("Cargo.toml").is_file() {
+            return Err(DayErr::NotFound(dir));
+        }
+        Ok(Day { name, dir })
+    }
+
+    /// Every day in the year directory, in order.
+    pub fn all(year_dir: &Path) -> Vec<Day> {
+        (1..=25)
+            .filter_map(|n| Day::find(year_dir, &n.to_string()).ok())
+            .collect()
+    }
+
+    pub fn src_dir(&self) -> PathBuf {
+        self.dir.join("src")
+    }
+
+    pub fn puzzle_dir(&self) -> PathBuf {
+        self.dir.join("puzzle")
+    }
+}
+
+/// The directory holding this crate and the days beside it.
+pub fn year_dir() -> PathBuf {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    manifest_dir
+        .parent()
+        .unwrap_or(manifest_dir)
+        .to_path_buf()
+}
+
+/// Every file under `dir`, recursively, in sorted order. Missing directories are empty.
+pub fn files_under(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut files = vec![];
+    for path in entries.filter_map(|e| e.ok().map(|e| e.path())) {
+        if path.is_dir() {
+            files.extend(files_under(&path));
+        } else {
+            files.push(path);
+        }
+    }
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find() {
+        let year = year_dir();
+        let day = Day::find(&year, "6").unwrap();
+        assert_eq!(day.name, "day06");
+        assert_eq!(Day::find(&year, "day06"), Ok(day.clone()));
+        assert_eq!(Day::find(&year, "06"), Ok(day));
+        assert_eq!(Day::find(&year, "26"), Err(DayErr::BadName("26".to_string())));
+        assert_eq!(Day::find(&year, "aoc"), Err(DayErr::BadName("aoc".to_string())));
+    }
+}
@@ -0,0 +1,136 @@
+use std::{fs, path::Path};
+
+use util::{
+    crypt::{self, CryptErr, Key},
+    input::INPUT_FILE,
+};
+
+use crate::day::Day;
+
+#[derive(Debug, PartialEq)]
+pub enum InputsErr {
+    UnknownConversion(String),
+    NoKey,
+    Io(String),
+    Crypt(String, CryptErr),
+}
+
+/// Which way `aoc inputs` converts each day's `input.txt`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Conversion {
+    Encrypt,
+    Decrypt,
+}
+
+impl Conversion {
+    pub fn parse(name: &str) -> Result<Conversion, InputsErr> {
+        match name {
+            "encrypt" => Ok(Conversion::Encrypt),
+            "decrypt" => Ok(Conversion::Decrypt),
+            _ => Err(InputsErr::UnknownConversion(name.to_string())),
+        }
+    }
+}
+
+fn io_err(path: &Path, err: std::io::Error) -> InputsErr {
+    InputsErr::Io(format!("{}: {}", path.display(), err))
+}
+
+/// Converts one day's input, writing the new file before removing the old one. Returns what
+/// was done, for reporting.
+fn convert_day(day: &Day, conversion: Conversion, key: &Key) -> Result<&'static str, InputsErr> {
+    let plain = day.puzzle_dir().join(INPUT_FILE);
+    let encrypted = crypt::encrypted_path(&plain);
+    let (from, to) = match conversion {
+        Conversion::Encrypt => (&plain, &encrypted),
+        Conversion::Decrypt => (&encrypted, &plain),
+    };
+    match (from.exists(), to.exists()) {
+        (false, false) => return Ok("no input"),
+        (false, true) => return Ok("already done"),
+        (true, true) => return Ok("both copies present, leaving them"),
+        (true, false) => (),
+    }
+
+    let data = fs::read(from).map_err(|e| io_err(from, e))?;
+    let converted = match conversion {
+        Conversion::Encrypt => key.encrypt(&data),
+        Conversion::Decrypt => key
+            .decrypt(&data)
+            .map_err(|e| InputsErr::Crypt(from.display().to_string(), e))?,
+    };
+    fs::write(to, converted).map_err(|e| io_err(to, e))?;
+    fs::remove_file(from).map_err(|e| io_err(from, e))?;
+    Ok(match conversion {
+        Conversion::Encrypt => "encrypted",
+        Conversion::Decrypt => "decrypted",
+    })
+}
+
+/// Encrypts or decrypts the real input of every day in `days`, stopping at the first failure
+/// so that a wrong key is noticed before it matters.
+pub fn convert(days: &[Day], conversion: Conversion, key: &Key) -> Result<(), InputsErr> {
+    for day in days {
+        println!("{}: {}", day.name, convert_day(day, conversion, key)?);
+    }
+    Ok(())
+}
+
+/// The key for `aoc inputs`, which unlike the days has nothing to fall back on without one.
+pub fn key() -> Result<Key, InputsErr> {
+    Key::find().ok_or(InputsErr::NoKey)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day_in(dir: &Path, name: &str, input: Option<&str>) -> Day {
+        let day = Day {
+            name: name.to_string(),
+            dir: dir.join(name),
+        };
+        fs::create_dir_all(day.puzzle_dir()).unwrap();
+        if let Some(input) = input {
+            fs::write(day.puzzle_dir().join(INPUT_FILE), input).unwrap();
+        }
+        day
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let dir = std::env::temp_dir().join(format!("aoc-inputs-{}", std::process::id()));
+        let days = [
+            day_in(&dir, "day01", Some("3   4\n")),
+            day_in(&dir, "day02", None),
+        ];
+        let plain = days[0].puzzle_dir().join(INPUT_FILE);
+        let key = Key::from_passphrase("correct horse").with_rounds(1000);
+
+        assert_eq!(convert_day(&days[0], Conversion::Encrypt, &key), Ok("encrypted"));
+        assert!(!plain.exists());
+        assert_eq!(convert_day(&days[0], Conversion::Encrypt, &key), Ok("already done"));
+        assert_eq!(convert_day(&days[1], Conversion::Encrypt, &key), Ok("no input"));
+
+        let wrong = Key::from_passphrase("battery staple").with_rounds(1000);
+        assert!(matches!(
+            convert_day(&days[0], Conversion::Decrypt, &wrong),
+            Err(InputsErr::Crypt(_, CryptErr::WrongKey))
+        ));
+        assert!(!plain.exists());
+
+        assert_eq!(convert(&days, Conversion::Decrypt, &key), Ok(()));
+        assert_eq!(fs::read_to_string(&plain).unwrap(), "3   4\n");
+        assert!(!crypt::encrypted_path(&plain).exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Conversion::parse("decrypt"), Ok(Conversion::Decrypt));
+        assert_eq!(
+            Conversion::parse("rot13"),
+            Err(InputsErr::UnknownConversion("rot13".to_string()))
+        );
+    }
+}
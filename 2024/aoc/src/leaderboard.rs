@@ -0,0 +1,423 @@
+//! Statistics from a private leaderboard's exported JSON, optionally set beside how long our own
+//! solutions take to run.
+//!
+//! The export has a `members` object keyed by id, each with a `name` (null for anonymous
+//! members) and `completion_day_level`, mapping day and part to the `get_star_ts` the star was
+//! earned at. Times are reported from when the day's puzzle unlocked, at midnight US Eastern.
+
+use std::{collections::BTreeMap, fs, path::Path, time::Duration};
+
+use serde_json::Value;
+
+use crate::watch::{parse_answers, RunAnswer};
+
+const PARTS: [(u8, &str); 2] = [(1, "A"), (2, "B")];
+const UNLOCK_HOUR_UTC: u64 = 5;
+
+#[derive(Debug, PartialEq)]
+pub enum LeaderboardErr {
+    Io(String),
+    BadJson(String),
+    Missing(&'static str),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Member {
+    pub id: u64,
+    pub name: String,
+    /// When each star was earned, keyed by day and part (1 or 2), in Unix seconds.
+    pub stars: BTreeMap<(u32, u8), u64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Leaderboard {
+    pub event: i64,
+    pub members: Vec<Member>,
+}
+
+/// Days since the Unix epoch of a date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+fn parse_ts(value: &Value) -> Option<u64> {
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+}
+
+impl Member {
+    fn parse(id: &str, member: &Value) -> Result<Member, LeaderboardErr> {
+        let id = id
+            .parse()
+            .map_err(|_| LeaderboardErr::BadJson(id.to_string()))?;
+        let name = match member["name"].as_str() {
+            Some(name) => name.to_string(),
+            None => format!("(anonymous user #{})", id),
+        };
+        let mut stars = BTreeMap::new();
+        if let Some(days) = member["completion_day_level"].as_object() {
+            for (day, parts) in days {
+                let day = day
+                    .parse()
+                    .map_err(|_| LeaderboardErr::BadJson(day.clone()))?;
+                for (part, star) in parts.as_object().into_iter().flatten() {
+                    let part = part
+                        .parse()
+                        .map_err(|_| LeaderboardErr::BadJson(part.clone()))?;
+                    let ts = parse_ts(&star["get_star_ts"])
+                        .ok_or(LeaderboardErr::Missing("get_star_ts"))?;
+                    stars.insert((day, part), ts);
+                }
+            }
+        }
+        Ok(Member { id, name, stars })
+    }
+
+    pub fn star_count(&self) -> usize {
+        self.stars.len()
+    }
+
+    pub fn last_star(&self) -> u64 {
+        self.stars.values().max().copied().unwrap_or(0)
+    }
+
+    /// How long after part A part B was solved.
+    pub fn delta(&self, day: u32) -> Option<u64> {
+        let a = self.stars.get(&(day, 1))?;
+        let b = self.stars.get(&(day, 2))?;
+        Some(b.saturating_sub(*a))
+    }
+}
+
+impl Leaderboard {
+    pub fn parse(json: &str) -> Result<Leaderboard, LeaderboardErr> {
+        let root: Value =
+            serde_json::from_str(json).map_err(|e| LeaderboardErr::BadJson(e.to_string()))?;
+        let event = root["event"]
+            .as_str()
+            .and_then(|e| e.parse().ok())
+            .or_else(|| root["event"].as_i64())
+            .ok_or(LeaderboardErr::Missing("event"))?;
+        let members = root["members"]
+            .as_object()
+            .ok_or(LeaderboardErr::Missing("members"))?
+            .iter()
+            .map(|(id, member)| Member::parse(id, member))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Leaderboard { event, members })
+    }
+
+    pub fn load(path: &Path) -> Result<Leaderboard, LeaderboardErr> {
+        let json = fs::read_to_string(path)
+            .map_err(|e| LeaderboardErr::Io(format!("{}: {}", path.display(), e)))?;
+        Leaderboard::parse(&json)
+    }
+
+    /// When a day's puzzle unlocked, in Unix seconds.
+    pub fn unlock(&self, day: u32) -> u64 {
+        let days = days_from_civil(self.event, 12, day as i64);
+        days as u64 * 86400 + UNLOCK_HOUR_UTC * 3600
+    }
+
+    /// How long after unlocking a member took to earn a star.
+    pub fn solve_time(&self, member: &Member, day: u32, part: u8) -> Option<u64> {
+        let ts = member.stars.get(&(day, part))?;
+        Some(ts.saturating_sub(self.unlock(day)))
+    }
+
+    pub fn days(&self) -> Vec<u32> {
+        let mut days: Vec<_> = self
+            .members
+            .iter()
+            .flat_map(|m| m.stars.keys().map(|(day, _)| *day))
+            .collect();
+        days.sort();
+        days.dedup();
+        days
+    }
+
+    /// The member who earned a star first, with their time.
+    pub fn fastest(&self, day: u32, part: u8) -> Option<(&Member, u64)> {
+        self.members
+            .iter()
+            .filter_map(|m| Some((m, self.solve_time(m, day, part)?)))
+            .min_by_key(|(m, time)| (*time, m.id))
+    }
+}
+
+/// Ways of ranking the members.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scoring {
+    /// The site's own local score: for each star, one point per member on the board, less one
+    /// for each member who earned it sooner.
+    Local,
+    /// Most stars, with the earlier last star breaking ties.
+    Stars,
+    /// Fewest total seconds between part A and part B, among those with the most days complete.
+    Delta,
+}
+
+impl Scoring {
+    pub const ALL: [Scoring; 3] = [Scoring::Local, Scoring::Stars, Scoring::Delta];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scoring::Local => "local",
+            Scoring::Stars => "stars",
+            Scoring::Delta => "delta",
+        }
+    }
+
+    /// Every member in rank order, each with a description of their score.
+    pub fn rank<'a>(&self, board: &'a Leaderboard) -> Vec<(&'a Member, String)> {
+        let mut members: Vec<&Member> = board.members.iter().collect();
+        match self {
+            Scoring::Local => {
+                let scores = local_scores(board);
+                members.sort_by_key(|m| (std::cmp::Reverse(scores[&m.id]), m.last_star(), m.id));
+                members
+                    .into_iter()
+                    .map(|m| (m, scores[&m.id].to_string()))
+                    .collect()
+            }
+            Scoring::Stars => {
+                members.sort_by_key(|m| (std::cmp::Reverse(m.star_count()), m.last_star(), m.id));
+                members
+                    .into_iter()
+                    .map(|m| (m, format!("{} stars", m.star_count())))
+                    .collect()
+            }
+            Scoring::Delta => {
+                let delta = |m: &Member| {
+                    let deltas: Vec<_> = board.days().iter().filter_map(|d| m.delta(*d)).collect();
+                    (deltas.len(), deltas.iter().sum::<u64>())
+                };
+                members.sort_by_key(|m| {
+                    let (days, total) = delta(m);
+                    (std::cmp::Reverse(days), total, m.id)
+                });
+                members
+                    .into_iter()
+                    .map(|m| {
+                        let (days, total) = delta(m);
+                        (m, format!("{} days, {} total", days, clock(total)))
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+fn local_scores(board: &Leaderboard) -> BTreeMap<u64, usize> {
+    let mut scores: BTreeMap<u64, usize> = board.members.iter().map(|m| (m.id, 0)).collect();
+    for day in board.days() {
+        for (part, _) in PARTS {
+            let mut solved: Vec<_> = board
+                .members
+                .iter()
+                .filter_map(|m| Some((m.stars.get(&(day, part))?, m.id)))
+                .collect();
+            solved.sort();
+            for (rank, (_, id)) in solved.iter().enumerate() {
+                *scores.entry(*id).or_default() += board.members.len() - rank;
+            }
+        }
+    }
+    scores
+}
+
+/// Seconds as `h:mm:ss`, with hours going past a day for late solves.
+pub fn clock(seconds: u64) -> String {
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Our fastest run of each day and part, from the records the days print with `--format json`.
+pub fn our_times(records: &str) -> BTreeMap<(u32, String), Duration> {
+    let mut times: BTreeMap<(u32, String), Duration> = BTreeMap::new();
+    let answers = parse_answers(records);
+    let timed = answers
+        .iter()
+        .filter(|a| a.item.is_none())
+        .filter_map(|a: &RunAnswer| {
+            let day = a.day.as_ref()?.trim_start_matches("day").parse().ok()?;
+            Some(((day, a.part.clone()), a.elapsed?))
+        });
+    for (key, elapsed) in timed {
+        let best = times.entry(key).or_insert(elapsed);
+        *best = (*best).min(elapsed);
+    }
+    times
+}
+
+/// The full report: each member's stars, the rankings, and each day's fastest solves beside our
+/// own run times if we have them.
+pub fn report(board: &Leaderboard, ours: &BTreeMap<(u32, String), Duration>) -> Vec<String> {
+    let mut lines = vec![format!("== Stars, {} (time after unlock)", board.event)];
+    let mut members: Vec<_> = board.members.iter().collect();
+    members.sort_by_key(|m| (&m.name, m.id));
+    for member in members {
+        lines.push(format!("{} ({} stars)", member.name, member.star_count()));
+        for day in board.days() {
+            let time = |part| {
+                board
+                    .solve_time(member, day, part)
+                    .map_or("-".to_string(), clock)
+            };
+            let delta = member
+                .delta(day)
+                .map_or(String::new(), |d| format!("  +{}", clock(d)));
+            if member.stars.contains_key(&(day, 1)) {
+                lines.push(format!(
+                    "  day {:02}  A {}  B {}{}",
+                    day,
+                    time(1),
+                    time(2),
+                    delta
+                ));
+            }
+        }
+    }
+
+    for scoring in Scoring::ALL {
+        lines.push(format!("== Ranking: {}", scoring.name()));
+        for (rank, (member, score)) in scoring.rank(board).iter().enumerate() {
+            lines.push(format!("{:>3}. {}  {}", rank + 1, member.name, score));
+        }
+    }
+
+    lines.push("== Days (fastest on the board, our run time)".to_string());
+    for day in board.days() {
+        let mut line = format!("day {:02}", day);
+        for (part, name) in PARTS {
+            let fastest = match board.fastest(day, part) {
+                Some((member, time)) => format!("{} by {}", clock(time), member.name),
+                None => "-".to_string(),
+            };
+            let ours = ours
+                .get(&(day, name.to_string()))
+                .map_or(String::new(), |t| format!(", ours {:?}", t));
+            line.push_str(&format!("  {} {}{}", name, fastest, ours));
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Day 1 of 2024 unlocked at 1733029200.
+    const EXPORT: &str = r#"{
+        "event": "2024",
+        "owner_id": 1,
+        "members": {
+            "1": {"id": 1, "name": "Ada", "stars": 3, "local_score": 0,
+                  "completion_day_level": {
+                      "1": {"1": {"get_star_ts": 1733029500, "star_index": 1},
+                            "2": {"get_star_ts": 1733029800, "star_index": 2}},
+                      "2": {"1": {"get_star_ts": 1733119200, "star_index": 5}}}},
+            "7": {"id": 7, "name": null, "stars": 2, "local_score": 0,
+                  "completion_day_level": {
+                      "1": {"1": {"get_star_ts": 1733029400, "star_index": 0},
+                            "2": {"get_star_ts": 1733030000, "star_index": 3}}}},
+            "9": {"id": 9, "name": "Grace", "stars": 0, "local_score": 0,
+                  "completion_day_level": {}}
+        }
+    }"#;
+
+    fn board() -> Leaderboard {
+        Leaderboard::parse(EXPORT).unwrap()
+    }
+
+    fn names(ranked: Vec<(&Member, String)>) -> Vec<(String, String)> {
+        ranked
+            .into_iter()
+            .map(|(m, s)| (m.name.clone(), s))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let board = board();
+        assert_eq!(board.event, 2024);
+        assert_eq!(board.members.len(), 3);
+        let anonymous = board.members.iter().find(|m| m.id == 7).unwrap();
+        assert_eq!(anonymous.name, "(anonymous user #7)");
+        assert_eq!(anonymous.stars[&(1, 2)], 1733030000);
+        assert_eq!(
+            Leaderboard::parse("{}"),
+            Err(LeaderboardErr::Missing("event"))
+        );
+    }
+
+    #[test]
+    fn test_times() {
+        let board = board();
+        assert_eq!(board.unlock(1), 1733029200);
+        assert_eq!(board.unlock(2), 1733029200 + 86400);
+        let ada = &board.members[0];
+        assert_eq!(board.solve_time(ada, 1, 1), Some(300));
+        assert_eq!(board.solve_time(ada, 2, 1), Some(3600));
+        assert_eq!(ada.delta(1), Some(300));
+        assert_eq!(ada.delta(2), None);
+        assert_eq!(board.fastest(1, 1).map(|(m, t)| (m.id, t)), Some((7, 200)));
+        assert_eq!(clock(90061), "25:01:01");
+    }
+
+    #[test]
+    fn test_rankings() {
+        let board = board();
+        assert_eq!(
+            names(Scoring::Local.rank(&board)),
+            vec![
+                ("Ada".to_string(), "8".to_string()),
+                ("(anonymous user #7)".to_string(), "5".to_string()),
+                ("Grace".to_string(), "0".to_string()),
+            ]
+        );
+        assert_eq!(names(Scoring::Stars.rank(&board))[0].1, "3 stars");
+        assert_eq!(
+            names(Scoring::Delta.rank(&board))[..2],
+            [
+                ("Ada".to_string(), "1 days, 0:05:00 total".to_string()),
+                (
+                    "(anonymous user #7)".to_string(),
+                    "1 days, 0:10:00 total".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_report_with_our_times() {
+        let records = concat!(
+            r#"{"day":"day01","part":"A","strategy":"default","item":null,"answer":"11","elapsed_us":1500}"#,
+            "\n",
+            r#"{"day":"day01","part":"A","strategy":"other","item":null,"answer":"11","elapsed_us":900}"#,
+            "\n",
+            r#"{"day":"day01","part":"A","strategy":null,"item":"row 1","answer":"2","elapsed_us":null}"#,
+        );
+        let ours = our_times(records);
+        assert_eq!(ours.len(), 1);
+        assert_eq!(ours[&(1, "A".to_string())], Duration::from_micros(900));
+
+        let lines = report(&board(), &ours);
+        assert!(lines.contains(&"  day 01  A 0:05:00  B 0:10:00  +0:05:00".to_string()));
+        assert!(lines.contains(&"  day 02  A 1:00:00  B -".to_string()));
+        assert!(lines.contains(
+            &"day 01  A 0:03:20 by (anonymous user #7), ours 900µs  B 0:10:00 by Ada".to_string()
+        ));
+    }
+}
@@ -0,0 +1,147 @@
+mod check;
+mod day;
+mod inputs;
+mod leaderboard;
+mod watch;
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use day::{Day, DayErr};
+use inputs::{Conversion, InputsErr};
+use leaderboard::{Leaderboard, LeaderboardErr};
+
+const USAGE: &str = "Usage: aoc watch <day> [-- <day arguments>]
+       aoc check [day]
+       aoc inputs <encrypt|decrypt>
+       aoc leaderboard <export.json> [--bench <records.jsonl>]
+
+--bench takes the records the days print with --format json, e.g. from
+for d in day*; do (cd $d && cargo run --release -- --format json); done";
+
+#[derive(Debug, PartialEq)]
+enum AocErr {
+    MissingArgument(&'static str),
+    UnknownCommand(String),
+    Day(DayErr),
+    Inputs(InputsErr),
+    Leaderboard(LeaderboardErr),
+}
+
+impl From<DayErr> for AocErr {
+    fn from(err: DayErr) -> Self {
+        AocErr::Day(err)
+    }
+}
+
+impl From<InputsErr> for AocErr {
+    fn from(err: InputsErr) -> Self {
+        AocErr::Inputs(err)
+    }
+}
+
+impl From<LeaderboardErr> for AocErr {
+    fn from(err: LeaderboardErr) -> Self {
+        AocErr::Leaderboard(err)
+    }
+}
+
+/// Splits `<day> [-- <day arguments>]` into the day and what to pass on to it.
+fn day_and_args(args: &[String]) -> Result<(Day, Vec<String>), AocErr> {
+    let name = args.first().ok_or(AocErr::MissingArgument("day"))?;
+    let day = Day::find(&day::year_dir(), name)?;
+    let rest = match args.get(1).map(|s| s.as_str()) {
+        Some("--") => args[2..].to_vec(),
+        _ => args[1..].to_vec(),
+    };
+    Ok((day, rest))
+}
+
+fn run(args: &[String]) -> Result<(), AocErr> {
+    let command = args.first().ok_or(AocErr::MissingArgument("command"))?;
+    match command.as_str() {
+        "watch" => {
+            let (day, day_args) = day_and_args(&args[1..])?;
+            watch::watch(&day, &day_args)
+        }
+        "check" => {
+            let all_days = Day::all(&day::year_dir());
+            let days = match args.get(1) {
+                Some(name) => vec![Day::find(&day::year_dir(), name)?],
+                None => all_days.clone(),
+            };
+            if !check::check(&days, &all_days) {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        "inputs" => {
+            let name = args.get(1).ok_or(AocErr::MissingArgument("encrypt or decrypt"))?;
+            let conversion = Conversion::parse(name)?;
+            let days = Day::all(&day::year_dir());
+            Ok(inputs::convert(&days, conversion, &inputs::key()?)?)
+        }
+        "leaderboard" => {
+            let path = args.get(1).ok_or(AocErr::MissingArgument("leaderboard export"))?;
+            let board = Leaderboard::load(Path::new(path))?;
+            let ours = match (args.get(2).map(|s| s.as_str()), args.get(3)) {
+                (Some("--bench"), Some(bench)) => {
+                    let records = fs::read_to_string(bench)
+                        .map_err(|e| LeaderboardErr::Io(format!("{}: {}", bench, e)))?;
+                    leaderboard::our_times(&records)
+                }
+                (Some("--bench"), None) => return Err(AocErr::MissingArgument("bench records")),
+                (Some(arg), _) => return Err(AocErr::UnknownCommand(arg.to_string())),
+                (None, _) => BTreeMap::new(),
+            };
+            leaderboard::report(&board, &ours)
+                .iter()
+                .for_each(|line| println!("{}", line));
+            Ok(())
+        }
+        _ => Err(AocErr::UnknownCommand(command.clone())),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(err) = run(&args) {
+        eprintln!("{:?}", err);
+        eprintln!("{}", USAGE);
+        std::process::exit(2);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_day_and_args() {
+        let (day, rest) = day_and_args(&args(&["16", "--", "--strategy", "all"])).unwrap();
+        assert_eq!(day.name, "day16");
+        assert_eq!(rest, args(&["--strategy", "all"]));
+        let (_, rest) = day_and_args(&args(&["day16"])).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(day_and_args(&[]), Err(AocErr::MissingArgument("day")));
+    }
+
+    #[test]
+    fn test_unknown_command() {
+        assert_eq!(
+            run(&args(&["solve"])),
+            Err(AocErr::UnknownCommand("solve".to_string()))
+        );
+        assert_eq!(
+            run(&args(&["inputs"])),
+            Err(AocErr::MissingArgument("encrypt or decrypt"))
+        );
+        assert_eq!(
+            run(&args(&["inputs", "rot13"])),
+            Err(AocErr::Inputs(InputsErr::UnknownConversion("rot13".to_string())))
+        );
+    }
+}
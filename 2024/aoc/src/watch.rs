@@ -0,0 +1,238 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::PathBuf,
+    process::{Command, Output},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use serde_json::Value;
+
+use crate::day::{files_under, Day};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Modification times of every watched file, so that any edit, new file or deletion shows up as
+/// a difference between two snapshots.
+#[derive(Debug, PartialEq)]
+pub struct Snapshot(BTreeMap<PathBuf, SystemTime>);
+
+impl Snapshot {
+    pub fn take(dirs: &[PathBuf]) -> Snapshot {
+        let files = dirs.iter().flat_map(|dir| files_under(dir)).filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        });
+        Snapshot(files.collect())
+    }
+}
+
+/// One answer from a run of a day, as read back from its `--format json` output.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunAnswer {
+    pub day: Option<String>,
+    pub part: String,
+    pub strategy: Option<String>,
+    pub item: Option<String>,
+    pub answer: String,
+    pub elapsed: Option<Duration>,
+}
+
+impl RunAnswer {
+    fn label(&self) -> String {
+        let mut label = format!("Part {}", self.part);
+        if let Some(strategy) = &self.strategy {
+            label.push_str(&format!(" ({})", strategy));
+        }
+        if let Some(item) = &self.item {
+            label.push_str(&format!(" {}", item));
+        }
+        label
+    }
+
+    fn same_answer(&self, other: &RunAnswer) -> bool {
+        (&self.part, &self.strategy, &self.item) == (&other.part, &other.strategy, &other.item)
+    }
+}
+
+/// Reads the JSON lines a day prints with `--format json`, skipping anything else.
+pub fn parse_answers(output: &str) -> Vec<RunAnswer> {
+    output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter_map(|record| {
+            let string = |key: &str| record.get(key)?.as_str().map(|s| s.to_string());
+            Some(RunAnswer {
+                day: string("day"),
+                part: string("part")?,
+                strategy: string("strategy"),
+                item: string("item"),
+                answer: string("answer")?,
+                elapsed: record["elapsed_us"].as_u64().map(Duration::from_micros),
+            })
+        })
+        .collect()
+}
+
+fn timing(current: Option<Duration>, previous: Option<Duration>) -> String {
+    match (current, previous) {
+        (Some(current), Some(previous)) => format!("  {:?}, was {:?}", current, previous),
+        (Some(current), None) => format!("  {:?}", current),
+        _ => String::new(),
+    }
+}
+
+/// One line per answer, marking those that changed since the previous run and those that are
+/// no longer produced.
+pub fn compare(previous: &[RunAnswer], current: &[RunAnswer]) -> Vec<String> {
+    let mut lines: Vec<_> = current
+        .iter()
+        .map(|answer| {
+            let before = previous.iter().find(|p| p.same_answer(answer));
+            let value = match before {
+                Some(before) if before.answer != answer.answer => {
+                    format!("{} -> {} (changed)", before.answer, answer.answer)
+                }
+                _ => answer.answer.clone(),
+            };
+            let elapsed = timing(answer.elapsed, before.and_then(|b| b.elapsed));
+            format!("{}: {}{}", answer.label(), value, elapsed)
+        })
+        .collect();
+    lines.extend(
+        previous
+            .iter()
+            .filter(|p| !current.iter().any(|c| c.same_answer(p)))
+            .map(|p| format!("{}: gone, was {}", p.label(), p.answer)),
+    );
+    lines
+}
+
+fn cargo(day: &Day, args: &[&str], extra: &[String]) -> std::io::Result<Output> {
+    Command::new("cargo")
+        .args(args)
+        .args(extra)
+        .current_dir(&day.dir)
+        .output()
+}
+
+fn show_failure(output: &Output) {
+    print!("{}", String::from_utf8_lossy(&output.stdout));
+    eprint!("{}", String::from_utf8_lossy(&output.stderr));
+}
+
+/// Runs the day's tests, then the day itself on its real input, and prints how the answers
+/// compare with `previous`. Returns the new answers, or `previous` again if the run failed.
+fn run_once(day: &Day, day_args: &[String], previous: Vec<RunAnswer>) -> Vec<RunAnswer> {
+    println!("== {}: rebuilding", day.name);
+    match cargo(day, &["test", "--quiet"], &[]) {
+        Ok(output) if output.status.success() => println!("examples: ok"),
+        Ok(output) => {
+            show_failure(&output);
+            println!("examples: FAILED");
+        }
+        Err(err) => println!("examples: could not run cargo: {}", err),
+    }
+
+    let run_args = ["run", "--release", "--quiet", "--", "--format", "json"];
+    match cargo(day, &run_args, day_args) {
+        Ok(output) if output.status.success() => {
+            let current = parse_answers(&String::from_utf8_lossy(&output.stdout));
+            compare(&previous, &current)
+                .iter()
+                .for_each(|line| println!("{}", line));
+            current
+        }
+        Ok(output) => {
+            show_failure(&output);
+            println!("run: FAILED");
+            previous
+        }
+        Err(err) => {
+            println!("run: could not run cargo: {}", err);
+            previous
+        }
+    }
+}
+
+/// Re-runs a day whenever anything under its `src/` or `puzzle/` changes. `day_args` are
+/// passed on to the day, e.g. `--strategy all`.
+pub fn watch(day: &Day, day_args: &[String]) -> ! {
+    let watched = [day.src_dir(), day.puzzle_dir()];
+    let mut last_snapshot = None;
+    let mut answers = vec![];
+    loop {
+        let snapshot = Snapshot::take(&watched);
+        if last_snapshot.as_ref() != Some(&snapshot) {
+            answers = run_once(day, day_args, answers);
+            println!("watching {} for changes", day.dir.display());
+            last_snapshot = Some(snapshot);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(part: &str, answer: &str, elapsed_us: u64) -> RunAnswer {
+        RunAnswer {
+            day: Some("day16".to_string()),
+            part: part.to_string(),
+            strategy: Some("default".to_string()),
+            item: None,
+            answer: answer.to_string(),
+            elapsed: Some(Duration::from_micros(elapsed_us)),
+        }
+    }
+
+    #[test]
+    fn test_parse_answers() {
+        let output = concat!(
+            r#"{"day":"day16","part":"A","strategy":"default","item":null,"answer":"7036","elapsed_us":1500}"#,
+            "\nnot a record\n",
+            r#"{"day":"day16","part":"B","strategy":null,"item":"machine 1","answer":"none","elapsed_us":null}"#,
+        );
+        let answers = parse_answers(output);
+        assert_eq!(answers.len(), 2);
+        assert_eq!(answers[0], answer("A", "7036", 1500));
+        assert_eq!(answers[1].item, Some("machine 1".to_string()));
+        assert_eq!(answers[1].elapsed, None);
+    }
+
+    #[test]
+    fn test_compare() {
+        let previous = vec![answer("A", "7036", 2000), answer("B", "45", 3000)];
+        let current = vec![answer("A", "7036", 1000), answer("B", "46", 3000)];
+        assert_eq!(
+            compare(&previous, &current),
+            vec![
+                "Part A (default): 7036  1ms, was 2ms",
+                "Part B (default): 45 -> 46 (changed)  3ms, was 3ms",
+            ]
+        );
+        assert_eq!(
+            compare(&previous, &current[..1]),
+            vec!["Part A (default): 7036  1ms, was 2ms", "Part B (default): gone, was 45"]
+        );
+        assert_eq!(compare(&[], &current[..1]), vec!["Part A (default): 7036  1ms"]);
+    }
+
+    #[test]
+    fn test_snapshot_sees_changes() {
+        let dir = std::env::temp_dir().join(format!("aoc-watch-{}", std::process::id()));
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        let watched = [dir.clone()];
+
+        let before = Snapshot::take(&watched);
+        fs::write(dir.join("nested").join("test.txt"), "1").unwrap();
+        let after = Snapshot::take(&watched);
+        assert_ne!(before, after);
+        assert_eq!(after, Snapshot::take(&watched));
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(Snapshot::take(&watched), Snapshot(BTreeMap::new()));
+    }
+}
@@ -1,18 +1,31 @@
-use std::collections::HashMap;
-
-fn parse_puzzle(puzzle: &str) -> (Vec<i32>, Vec<i32>) {
-    let (left, right) = puzzle.lines()
-        .map(|s| s
-                .split_whitespace()
-                .filter_map(|x| x.parse::<i32>().ok())
-                .collect::<Vec<_>>())
-        .fold( (vec![], vec![]), |(mut left, mut right), line| {
-            left.push(line[0]);
-            right.push(line[1]);
-            (left, right)
-        });
+use std::{
+    collections::HashMap,
+    io::{self, BufRead},
+};
+
+use util::runner::Runner;
 
-    (left, right)
+/// Reads the two lists a line at a time, so only the numbers are ever held. Every line must be
+/// exactly two integers.
+fn parse_lists<R: BufRead>(reader: R) -> io::Result<(Vec<i32>, Vec<i32>)> {
+    let mut left = vec![];
+    let mut right = vec![];
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let numbers: Vec<i32> = line
+            .split_whitespace()
+            .map(|x| x.parse::<i32>())
+            .collect::<Result<_, _>>()
+            .unwrap_or_default();
+        let [l, r] = numbers[..] else {
+            let msg = format!("line {}: expected two integers, got {:?}", idx + 1, line);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+        };
+        left.push(l);
+        right.push(r);
+    }
+
+    Ok((left, right))
 }
 
 fn part_a(mut left: Vec<i32>, mut right: Vec<i32>) -> i32 {
@@ -37,10 +50,40 @@ fn part_b(left: &[i32], right: &[i32]) -> i32 {
 }
 
 fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
-    let (left, right) = parse_puzzle(puzzle);
-    let a = part_a(left.clone(), right.clone());
-    println!("Part A: {}", a);
-    let b = part_b(&left, &right);
-    println!("Part B: {}", b);
+    let runner = Runner::from_args();
+    let input = runner.open_input(util::input_path!()).expect("Unable to open puzzle input");
+    let (left, right) = parse_lists(input).expect("Unable to read puzzle input");
+    runner.solve("A", || part_a(left.clone(), right.clone()));
+    runner.solve("B", || part_b(&left, &right));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use util::{
+        example::{self, ExampleErr},
+        output::Answer,
+    };
+
+    #[test]
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            let (left, right) = parse_lists(example.puzzle.as_bytes()).unwrap();
+            match part {
+                "A" => Ok(part_a(left.clone(), right.clone()).render()),
+                "B" => Ok(part_b(&left, &right).render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse_lists("3   4\n4   3\n".as_bytes()).unwrap(), (vec![3, 4], vec![4, 3]));
+        for bad in ["3   4\n4\n", "3   4\n\n4   3\n", "3   4\n4   x\n", "3   4\n4   3   5\n"] {
+            let err = parse_lists(bad.as_bytes()).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            assert!(err.to_string().starts_with("line 2: "), "{}", err);
+        }
+    }
 }
@@ -1,26 +1,47 @@
-use std::str::FromStr;
+use std::io::{self, BufRead};
 
-#[derive(Debug)]
-struct ReportGrid {
-    grid: Vec<Vec<i32>>,
+use util::runner::Runner;
+
+/// How many reports are safe, counted a line at a time so that the reports are never all held
+/// at once. Every line must be a report of at least one integer.
+#[derive(Debug, Default, PartialEq)]
+struct ReportTally {
+    safe: usize,
+    safe_dampened: usize,
 }
 
-#[derive(Debug)]
-enum ReportGridError {}
-
-impl FromStr for ReportGrid {
-    type Err = ReportGridError;
-    fn from_str(puzzle: &str) -> Result<Self, Self::Err> {
-        let grid = puzzle
-            .lines()
-            .map(|line| {
-                line.split_whitespace()
-                    .filter_map(|x| x.parse().ok())
-                    .collect()
-            })
-            .collect();
-
-        Ok(ReportGrid { grid })
+impl ReportTally {
+    fn from_reader<R: BufRead>(reader: R) -> io::Result<ReportTally> {
+        let mut tally = ReportTally::default();
+        for (idx, line) in reader.lines().enumerate() {
+            let bad_line = |msg: String| {
+                let msg = format!("line {}: {}", idx + 1, msg);
+                io::Error::new(io::ErrorKind::InvalidData, msg)
+            };
+            let report: Vec<i32> = line?
+                .split_whitespace()
+                .map(|x| x.parse().map_err(|_| bad_line(format!("{:?} is not an integer", x))))
+                .collect::<io::Result<_>>()?;
+            if report.is_empty() {
+                return Err(bad_line("empty report".to_string()));
+            }
+            if is_safe(&report) {
+                tally.safe += 1;
+            }
+            if is_safe_dampened(&report) {
+                tally.safe_dampened += 1;
+            }
+        }
+
+        Ok(tally)
+    }
+
+    fn part_a(&self) -> usize {
+        self.safe
+    }
+
+    fn part_b(&self) -> usize {
+        self.safe_dampened
     }
 }
 
@@ -67,27 +88,47 @@ fn is_safe_dampened(report: &[i32]) -> bool {
     false
 }
 
-impl ReportGrid {
-    fn part_a(&self) -> usize {
-        let count = self.grid.iter().filter(|report| is_safe(report)).count();
-
-        count
-    }
+fn main() {
+    let runner = Runner::from_args();
+    let input = runner.open_input(util::input_path!()).expect("Unable to open puzzle input");
+    let tally = ReportTally::from_reader(input).expect("Unable to read puzzle input");
+    runner.solve("A", || tally.part_a());
+    runner.solve("B", || tally.part_b());
+}
 
-    fn part_b(&self) -> usize {
-        let count = self
-            .grid
-            .iter()
-            .filter(|report| is_safe_dampened(report))
-            .count();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use util::{
+        example::{self, ExampleErr},
+        output::Answer,
+    };
 
-        count
+    #[test]
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            let tally = ReportTally::from_reader(example.puzzle.as_bytes()).unwrap();
+            match part {
+                "A" => Ok(tally.part_a().render()),
+                "B" => Ok(tally.part_b().render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
     }
-}
 
-fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
-    let grid = ReportGrid::from_str(puzzle).expect("Unable to parse input puzzle");
-    println!("Part A: {}", grid.part_a());
-    println!("Part B: {}", grid.part_b());
+    #[test]
+    fn test_parse_errors() {
+        let tally = |puzzle: &str| ReportTally::from_reader(puzzle.as_bytes());
+        assert_eq!(tally("1 2 3\n9 5 1\n").unwrap().safe, 1);
+        for (bad, msg) in [
+            ("1 2 3\n1 x 3\n", "line 2: \"x\" is not an integer"),
+            ("1 2 3\n1 2 3.5\n", "line 2: \"3.5\" is not an integer"),
+            ("1 2 3\n\n1 2 3\n", "line 2: empty report"),
+            ("1 2 3\n   \n", "line 2: empty report"),
+        ] {
+            let err = tally(bad).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            assert_eq!(err.to_string(), msg);
+        }
+    }
 }
@@ -1,5 +1,6 @@
 use std::str::FromStr;
 use regex::Regex;
+use util::runner::Runner;
 
 #[derive(Debug, PartialEq)]
 enum Instruction {
@@ -63,15 +64,20 @@ impl TobogganComputer {
 }
 
 fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
+    let runner = Runner::from_args();
+    let puzzle = runner.read_input(util::input_path!()).expect("Unable to read puzzle input");
     let computer =
-        TobogganComputer::from_str(puzzle).expect("Unable to parse computer instructions.");
-    println!("Part A: {}", computer.execute());
+        TobogganComputer::from_str(&puzzle).expect("Unable to parse computer instructions.");
+    runner.solve("A", || computer.execute());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use util::{
+        example::{self, ExampleErr},
+        output::Answer,
+    };
 
     #[test]
     fn test_computer_executes_mul_only() {
@@ -117,17 +123,13 @@ mod tests {
     }
 
     #[test]
-    fn test_test_txt() {
-        let instr = include_str!("../puzzle/test.txt");
-        let result = TobogganComputer::from_str(instr).unwrap().execute();
-        assert_eq!(161, result);
-    }
-
-    #[test]
-    fn test_test_do_dont_txt() {
-        let instr = include_str!("../puzzle/test_do_dont.txt");
-        let result = TobogganComputer::from_str(instr).unwrap();
-        println!("{:?}", result.instructions);
-        assert_eq!(48, result.execute());
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            let computer = TobogganComputer::from_str(&example.puzzle).unwrap();
+            match part {
+                "A" => Ok(computer.execute().render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
     }
 }
@@ -4,11 +4,34 @@ mod point;
 mod wordsearch_grid;
 
 use std::str::FromStr;
+use util::runner::Runner;
 use wordsearch_grid::WordsearchGrid;
 
 fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
-    let grid = WordsearchGrid::from_str(puzzle).unwrap();
-    println!("Part A: {}", grid.part_a());
-    println!("Part B: {}", grid.part_b());
+    let runner = Runner::from_args();
+    let puzzle = runner.read_input(util::input_path!()).expect("Unable to read puzzle input");
+    let grid = WordsearchGrid::from_str(&puzzle).unwrap();
+    runner.solve("A", || grid.part_a());
+    runner.solve("B", || grid.part_b());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use util::{
+        example::{self, ExampleErr},
+        output::Answer,
+    };
+
+    #[test]
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            let grid = WordsearchGrid::from_str(&example.puzzle).unwrap();
+            match part {
+                "A" => Ok(grid.part_a().render()),
+                "B" => Ok(grid.part_b().render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
+    }
 }
@@ -1,5 +1,7 @@
 use std::{collections::HashMap, str::FromStr};
 
+use util::runner::Runner;
+
 struct PrintQueueChecker {
     rules: HashMap<i32, Vec<i32>>,
     page_numbers: Vec<Vec<i32>>,
@@ -101,16 +103,21 @@ impl PrintQueueChecker {
 }
 
 fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
-    let checker = PrintQueueChecker::from_str(puzzle).expect("Unable to parse checker");
-    println!("Part A: {}", checker.part_a());
+    let runner = Runner::from_args();
+    let puzzle = runner.read_input(util::input_path!()).expect("Unable to read puzzle input");
+    let checker = PrintQueueChecker::from_str(&puzzle).expect("Unable to parse checker");
+    runner.solve("A", || checker.part_a());
     // 6017 is too high
-    println!("Part B: {}", checker.part_b());
+    runner.solve("B", || checker.part_b());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use util::{
+        example::{self, ExampleErr},
+        output::Answer,
+    };
 
     #[test]
     fn test_puzzle_parses_correctly() {
@@ -163,16 +170,14 @@ mod tests {
     }
 
     #[test]
-    fn test_test_txt_part_a() {
-        let input = include_str!("../puzzle/test.txt");
-        let checker = PrintQueueChecker::from_str(input).unwrap();
-        assert_eq!(checker.part_a(), 143);
-    }
-
-    #[test]
-    fn test_test_txt_part_b() {
-        let input = include_str!("../puzzle/test.txt");
-        let checker = PrintQueueChecker::from_str(input).unwrap();
-        assert_eq!(checker.part_b(), 123);
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            let checker = PrintQueueChecker::from_str(&example.puzzle).unwrap();
+            match part {
+                "A" => Ok(checker.part_a().render()),
+                "B" => Ok(checker.part_b().render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
     }
 }
@@ -1,5 +1,13 @@
-use std::{collections::HashSet, str::FromStr};
-use util::{direction::Direction, point::Point};
+use std::str::FromStr;
+use util::{
+    bitgrid::{BitGrid, DirectedBitGrid},
+    cycle::{self, Visited},
+    direction::Direction,
+    explorer::{Explorer, Frame, Simulation},
+    point::Point,
+    runner::Runner,
+    tui,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct FacingPoint {
@@ -7,9 +15,15 @@ struct FacingPoint {
     dir: Direction,
 }
 
+impl Visited<FacingPoint> for DirectedBitGrid {
+    fn insert(&mut self, guard: &FacingPoint) -> bool {
+        DirectedBitGrid::insert(self, &guard.pos, &guard.dir)
+    }
+}
+
 #[derive(Debug)]
 struct GuardedLab {
-    obstacles: HashSet<Point>,
+    obstacles: BitGrid,
     guard_start: FacingPoint,
     height: usize,
     width: usize,
@@ -23,7 +37,7 @@ enum GuardedLabErr {
 impl FromStr for GuardedLab {
     type Err = GuardedLabErr;
     fn from_str(puzzle: &str) -> Result<Self, Self::Err> {
-        let mut obstacles = HashSet::new();
+        let mut obstacle_points = vec![];
         let mut guard: Option<FacingPoint> = None;
         let mut height = 0;
         let mut width = 0;
@@ -31,7 +45,7 @@ impl FromStr for GuardedLab {
             for (col_idx, cell) in row.chars().enumerate() {
                 let p = Point(row_idx as i32, col_idx as i32);
                 match cell {
-                    '#' => _ = obstacles.insert(p),
+                    '#' => obstacle_points.push(p),
                     '^' => {
                         guard = Some(FacingPoint {
                             pos: p,
@@ -69,6 +83,9 @@ impl FromStr for GuardedLab {
             None => return Err(GuardedLabErr::GuardNotFound),
         };
 
+        let mut obstacles = BitGrid::new(height, width);
+        obstacle_points.iter().for_each(|p| _ = obstacles.insert(p));
+
         Ok(GuardedLab {
             obstacles,
             guard_start,
@@ -81,18 +98,15 @@ impl FromStr for GuardedLab {
 impl GuardedLab {
 
     fn in_bounds(&self, point: &Point) -> bool {
-        point.0 >= 0 &&
-            point.1 >= 0 &&
-            point.0 < (self.height as i32) &&
-            point.1 < (self.width as i32)
+        self.obstacles.in_bounds(point)
     }
 
-    fn get_guard_path(&self) -> HashSet<Point> {
-        let mut travelled: HashSet<Point> = HashSet::new();
+    fn get_guard_path(&self) -> BitGrid {
+        let mut travelled = BitGrid::new(self.height, self.width);
         let mut guard = self.guard_start;
         
         while self.in_bounds(&guard.pos) {
-            travelled.insert(guard.pos);
+            travelled.insert(&guard.pos);
             let next_pos = guard.pos.add(&guard.dir.to_point());
             if self.obstacles.contains(&next_pos) {
                 guard = FacingPoint { pos: guard.pos, dir: guard.dir.right90()};
@@ -104,27 +118,19 @@ impl GuardedLab {
         travelled
     }
 
-    fn causes_loop(&self, obstacle: &Point) -> bool {
-        let mut travelled: HashSet<FacingPoint> = HashSet::new();
-        let mut guard = self.guard_start;
-
-        while self.in_bounds(&guard.pos) {
-            travelled.insert(guard);
-            let next_pos = guard.pos.add(&guard.dir.to_point());
-            if self.obstacles.contains(&next_pos) || *obstacle == next_pos {
-                guard = FacingPoint { pos: guard.pos, dir: guard.dir.right90()};
-                if travelled.contains(&guard) {
-                    return true;
-                }
-                continue;
-            }
-
-            guard = FacingPoint { pos: next_pos, dir: guard.dir };
-            if travelled.contains(&guard) {
-                return true;
-            }
+    fn step(&self, guard: &FacingPoint, obstacle: &Point) -> Option<FacingPoint> {
+        let next_pos = guard.pos.add(&guard.dir.to_point());
+        if self.obstacles.contains(&next_pos) || *obstacle == next_pos {
+            return Some(FacingPoint { pos: guard.pos, dir: guard.dir.right90() });
         }
-        false
+        Some(FacingPoint { pos: next_pos, dir: guard.dir })
+            .filter(|g| self.in_bounds(&g.pos))
+    }
+
+    fn causes_loop(&self, obstacle: &Point, visited: &mut DirectedBitGrid) -> bool {
+        visited.clear();
+        let next = |guard: &FacingPoint| self.step(guard, obstacle);
+        cycle::revisits(self.guard_start, next, visited)
     }
 
     fn part_a(&self) -> usize {
@@ -133,32 +139,94 @@ impl GuardedLab {
     }
 
     fn part_b(&self) -> usize {
+        let mut visited = DirectedBitGrid::new(self.height, self.width);
         self.get_guard_path()
             .iter()
-            .filter(|pos| self.causes_loop(pos))
+            .filter(|pos| self.causes_loop(pos, &mut visited))
             .count()
     }
 }
 
+/// The guard's walk through the lab, one move or turn per step, for the explorer.
+#[derive(Debug, Clone)]
+struct Patrol<'a> {
+    lab: &'a GuardedLab,
+    guard: Option<FacingPoint>,
+    travelled: BitGrid,
+}
+
+impl<'a> Patrol<'a> {
+    fn new(lab: &'a GuardedLab) -> Patrol<'a> {
+        let mut travelled = BitGrid::new(lab.height, lab.width);
+        travelled.insert(&lab.guard_start.pos);
+        Patrol {
+            lab,
+            guard: Some(lab.guard_start),
+            travelled,
+        }
+    }
+}
+
+impl Simulation for Patrol<'_> {
+    fn step(&mut self) -> bool {
+        let Some(guard) = self.guard else {
+            return false;
+        };
+        self.guard = self.lab.step(&guard, &Point(-1, -1));
+        if let Some(g) = self.guard {
+            self.travelled.insert(&g.pos);
+        }
+        true
+    }
+
+    fn frame(&self) -> Frame {
+        let mut frame = Frame::new(self.lab.height, self.lab.width, '.');
+        self.lab.obstacles.iter().for_each(|p| frame.set(&p, '#'));
+        self.travelled.iter().for_each(|p| frame.set(&p, 'X'));
+        if let Some(guard) = self.guard {
+            let cell = match guard.dir {
+                Direction::Up => '^',
+                Direction::Right => '>',
+                Direction::Down => 'v',
+                Direction::Left => '<',
+                _ => '*',
+            };
+            frame.set(&guard.pos, cell);
+        }
+        frame
+    }
+}
+
 fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
-    let lab = GuardedLab::from_str(puzzle).expect("Unable to parse GuardedLab");
-    println!("Part A: {}", lab.part_a());
-    println!("Part B: {}", lab.part_b());
+    let runner = Runner::from_args();
+    let puzzle = runner.read_input(util::input_path!()).expect("Unable to read puzzle input");
+    let lab = GuardedLab::from_str(&puzzle).expect("Unable to parse GuardedLab");
+    if runner.explore().is_some() {
+        let explorer = Explorer::new(Patrol::new(&lab))
+            .with_metric("visited", |patrol: &Patrol| patrol.travelled.len() as f64);
+        tui::explore(explorer).expect("Unable to run the explorer");
+        return;
+    }
+    runner.solve("A", || lab.part_a());
+    runner.solve("B", || lab.part_b());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use util::{
+        example::{self, ExampleErr},
+        output::Answer,
+    };
 
     #[test]
     fn test_parse_ok() {
         let input = "#.#\n>.#";
         let expected_obstacles = {
-            let mut obs = HashSet::new();
-            obs.insert(Point(0, 0));
-            obs.insert(Point(0, 2));
-            obs.insert(Point(1, 2));
+            let mut obs = BitGrid::new(2, 3);
+            obs.insert(&Point(0, 0));
+            obs.insert(&Point(0, 2));
+            obs.insert(&Point(1, 2));
             obs
         };
         let expected_guard = FacingPoint {
@@ -177,24 +245,33 @@ mod tests {
     }
 
     #[test]
-    fn test_test_txt_part_a() {
-        let input = include_str!("../puzzle/test.txt");
+    fn test_guard_is_in_loop() {
+        let input = ".#.\n#^#\n...";
+        let new_obs = Point(2, 1);
         let lab = GuardedLab::from_str(input).unwrap();
-        assert_eq!(lab.part_a(), 41);
+        let mut visited = DirectedBitGrid::new(lab.height, lab.width);
+        assert!(lab.causes_loop(&new_obs, &mut visited));
     }
 
     #[test]
-    fn test_test_txt_part_b() {
-        let input = include_str!("../puzzle/test.txt");
-        let lab = GuardedLab::from_str(input).unwrap();
-        assert_eq!(lab.part_b(), 6);
+    fn test_patrol_visits_guard_path() {
+        let lab = GuardedLab::from_str(".#.\n..#\n.^.").unwrap();
+        let mut patrol = Patrol::new(&lab);
+        assert_eq!(patrol.frame().to_string(), ".#.\n..#\n.^.\n");
+        while patrol.step() {}
+        assert_eq!(patrol.travelled, lab.get_guard_path());
+        assert_eq!(patrol.frame().to_string(), ".#.\n.X#\n.X.\n");
     }
 
     #[test]
-    fn test_guard_is_in_loop() {
-        let input = ".#.\n#^#\n...";
-        let new_obs = Point(2, 1);
-        let lab = GuardedLab::from_str(input).unwrap();
-        assert!(lab.causes_loop(&new_obs));
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            let lab = GuardedLab::from_str(&example.puzzle).unwrap();
+            match part {
+                "A" => Ok(lab.part_a().render()),
+                "B" => Ok(lab.part_b().render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
     }
 }
@@ -1,42 +1,57 @@
-use std::{collections::HashMap, str::FromStr};
+use std::io::BufRead;
 
-#[derive(Debug)]
+use util::runner::Runner;
+
+/// The totals of the calibrations that can be made true, checked a line at a time so that the
+/// equations are never all held at once.
+#[derive(Debug, Default, PartialEq)]
 struct CalibrationSet {
-    calibrations: HashMap<i64, Vec<i64>>,
+    total: i64,
+    total_with_concat: i64,
 }
 
 #[derive(Debug)]
 enum CalibrationSetErr {
+    ReadFailed,
     KeyMissing,
     KeyParseFailed,
     ValuesMissing,
 }
-impl FromStr for CalibrationSet {
-    type Err = CalibrationSetErr;
-    fn from_str(puzzle: &str) -> Result<Self, Self::Err> {
-        let mut calibrations = HashMap::new();
-        for line in puzzle.lines() {
-            let key = line
-                .split(":")
-                .nth(0)
-                .ok_or(CalibrationSetErr::KeyMissing)?
-                .parse()
-                .map_err(|_| CalibrationSetErr::KeyParseFailed)?;
-            let values = line
-                .split(":")
-                .nth(1)
-                .ok_or(CalibrationSetErr::ValuesMissing)?
-                .split(" ")
-                .filter_map(|x| x.parse().ok())
-                .collect();
-            calibrations.insert(key, values);
-        }
 
-        Ok(CalibrationSet { calibrations })
-    }
+fn parse_equation(line: &str) -> Result<(i64, Vec<i64>), CalibrationSetErr> {
+    let key = line
+        .split(":")
+        .next()
+        .ok_or(CalibrationSetErr::KeyMissing)?
+        .parse()
+        .map_err(|_| CalibrationSetErr::KeyParseFailed)?;
+    let values = line
+        .split(":")
+        .nth(1)
+        .ok_or(CalibrationSetErr::ValuesMissing)?
+        .split(" ")
+        .filter_map(|x| x.parse().ok())
+        .collect();
+    Ok((key, values))
 }
 
 impl CalibrationSet {
+    fn from_reader<R: BufRead>(reader: R) -> Result<CalibrationSet, CalibrationSetErr> {
+        let mut set = CalibrationSet::default();
+        for line in reader.lines() {
+            let line = line.map_err(|_| CalibrationSetErr::ReadFailed)?;
+            let (key, vals) = parse_equation(&line)?;
+            if CalibrationSet::combination_possible_no_concat(key, &vals) {
+                set.total += key;
+            }
+            if CalibrationSet::combination_possible_with_concat(key, &vals) {
+                set.total_with_concat += key;
+            }
+        }
+
+        Ok(set)
+    }
+
     fn combination_possible_no_concat(key: i64, vals: &[i64]) -> bool {
         let reversed = vals.iter().rev().copied().collect::<Vec<i64>>();
         CalibrationSet::all_combinations_no_concat(&reversed).contains(&key)
@@ -79,32 +94,29 @@ impl CalibrationSet {
     }
 
     fn part_a(&self) -> usize {
-        self.calibrations
-            .iter()
-            .filter(|(&key, vals)| CalibrationSet::combination_possible_no_concat(key, vals))
-            .map(|(&key, _)| key)
-            .sum::<i64>() as usize
+        self.total as usize
     }
 
     fn part_b(&self) -> usize {
-        self.calibrations
-            .iter()
-            .filter(|(&key, vals)| CalibrationSet::combination_possible_with_concat(key, vals))
-            .map(|(&key, _)| key)
-            .sum::<i64>() as usize
+        self.total_with_concat as usize
     }
 }
 
 fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
-    let calibs = CalibrationSet::from_str(puzzle).unwrap();
-    println!("Part A: {}", calibs.part_a());
-    println!("Part B: {}", calibs.part_b());
+    let runner = Runner::from_args();
+    let input = runner.open_input(util::input_path!()).expect("Unable to open puzzle input");
+    let calibs = CalibrationSet::from_reader(input).unwrap();
+    runner.solve("A", || calibs.part_a());
+    runner.solve("B", || calibs.part_b());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use util::{
+        example::{self, ExampleErr},
+        output::Answer,
+    };
 
     #[test]
     fn test_short_valid_line_no_concat() {
@@ -154,16 +166,14 @@ mod tests {
     }
 
     #[test]
-    fn test_test_txt_no_concat() {
-        let puzzle = include_str!("../puzzle/test.txt");
-        let set = CalibrationSet::from_str(puzzle).unwrap();
-        assert_eq!(set.part_a(), 3749);
-    }
-
-    #[test]
-    fn test_test_txt_with_concat() {
-        let puzzle = include_str!("../puzzle/test.txt");
-        let set = CalibrationSet::from_str(puzzle).unwrap();
-        assert_eq!(set.part_b(), 11387);
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            let set = CalibrationSet::from_reader(example.puzzle.as_bytes()).unwrap();
+            match part {
+                "A" => Ok(set.part_a().render()),
+                "B" => Ok(set.part_b().render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
     }
 }
@@ -2,7 +2,7 @@ use std::{
     collections::{HashMap, HashSet},
     str::FromStr,
 };
-use util::point::Point;
+use util::{point::Point, runner::Runner};
 
 #[derive(Debug)]
 struct AntennaMap {
@@ -95,41 +95,30 @@ impl AntennaMap {
 }
 
 fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
-    let map = AntennaMap::from_str(puzzle).unwrap();
-    println!("Part A: {}", map.part_a());
-    println!("Part B: {}", map.part_b());
+    let runner = Runner::from_args();
+    let puzzle = runner.read_input(util::input_path!()).expect("Unable to read puzzle input");
+    let map = AntennaMap::from_str(&puzzle).unwrap();
+    runner.solve("A", || map.part_a());
+    runner.solve("B", || map.part_b());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use util::{
+        example::{self, ExampleErr},
+        output::Answer,
+    };
 
     #[test]
-    fn test_test_small_txt_part_a() {
-        let puzzle = include_str!("../puzzle/test_small.txt");
-        let map = AntennaMap::from_str(puzzle).unwrap();
-        assert_eq!(2, map.part_a());
-    }
-
-    #[test]
-    fn test_test_small_txt_part_b() {
-        let puzzle = include_str!("../puzzle/test_small.txt");
-        let map = AntennaMap::from_str(puzzle).unwrap();
-        assert_eq!(5, map.part_b());
-    }
-
-    #[test]
-    fn test_test_txt_part_a() {
-        let puzzle = include_str!("../puzzle/test.txt");
-        let map = AntennaMap::from_str(puzzle).unwrap();
-        assert_eq!(14, map.part_a());
-    }
-
-    #[test]
-    fn test_test_txt_part_b() {
-        let puzzle = include_str!("../puzzle/test.txt");
-        let map = AntennaMap::from_str(puzzle).unwrap();
-        assert_eq!(34, map.part_b());
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            let map = AntennaMap::from_str(&example.puzzle).unwrap();
+            match part {
+                "A" => Ok(map.part_a().render()),
+                "B" => Ok(map.part_b().render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
     }
 }
@@ -1,4 +1,6 @@
-use std::{collections::HashSet, num::ParseIntError, str::FromStr};
+use std::{num::ParseIntError, str::FromStr};
+
+use util::{interval::RangeSet, runner::Runner};
 
 #[derive(Debug)]
 struct DiskDefrag {
@@ -67,122 +69,53 @@ impl DiskDefrag {
     }
 
     fn part_b(&self) -> usize {
-        let mut unaccounted: i32 = self.pairs.iter().map(|(x, _)| x).sum();
-        let mut acc = 0;
-        let mut start_idx = 0;
-        let end_idx = self.pairs.len() - 1;
-        let mut memory_idx: usize = 0;
-
-        let mut copied_memory = HashSet::new();
-
-        'outer: while unaccounted > 0 {
-
-            let (start_occ, start_free) = self.pairs.get(start_idx).expect("Start idx not found!");
-
-            if copied_memory.contains(&start_idx) {
-                let (occ, _) = self.pairs.get(start_idx).expect("Start index out of bounds");
-                memory_idx += *occ as usize;
-            } else {
-                // Take from front for start spaces occupied
-                for _ in 0..*start_occ {
-                    acc += start_idx * memory_idx;
-                    memory_idx += 1;
-                    unaccounted -= 1;
-                    if unaccounted <= 0 {
-                        break 'outer;
-                    }
-                }
-            }
-
-            start_idx += 1;
-            let mut start_free = *start_free;
-            while start_free > 0 {
-                let mut memory_copied = false;
-                for idx in (start_idx..=end_idx).rev() {
-                    if copied_memory.contains(&idx) {
-                        continue;
-                    }
-
-                    let (end_occ, _) = self.pairs.get(idx).unwrap();
-                    if *end_occ > start_free {
-                        continue;
-                    }
-
-                    // Copy memory over
-                    memory_copied = true;
-                    for _ in 0..*end_occ {
-                        acc += idx * memory_idx;
-                        memory_idx += 1;
-                        unaccounted -= 1;
-                        if unaccounted <= 0 {
-                            break 'outer;
-                        }
-                    }
-                    start_free -= *end_occ;
-
-                    copied_memory.insert(idx);
-                }
+        let mut files = vec![];
+        let mut free = RangeSet::new();
+        let mut position = 0;
+        for &(occupied, unoccupied) in &self.pairs {
+            let (occupied, unoccupied) = (occupied as i64, unoccupied as i64);
+            files.push(position..position + occupied);
+            free.insert(position + occupied..position + occupied + unoccupied);
+            position += occupied + unoccupied;
+        }
 
-                if !memory_copied {
-                    memory_idx += start_free as usize;
-                    break;
-                }
+        for file in files.iter_mut().rev() {
+            let len = file.end - file.start;
+            let target = free
+                .iter()
+                .take_while(|span| span.end <= file.start)
+                .find(|span| span.end - span.start >= len);
+            if let Some(span) = target {
+                let moved = span.start..span.start + len;
+                free.remove(moved.clone());
+                free.insert(file.clone());
+                *file = moved;
             }
         }
 
-        acc
+        files
+            .iter()
+            .enumerate()
+            .map(|(id, file)| file.clone().map(|pos| id * pos as usize).sum::<usize>())
+            .sum()
     }
 }
 
 fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
-    let defrag = DiskDefrag::from_str(puzzle).expect("Unable to parse input");
-    println!("Part A: {}", defrag.part_a());
-    println!("Part B: {}", defrag.part_b());
+    let runner = Runner::from_args();
+    let puzzle = runner.read_input(util::input_path!()).expect("Unable to read puzzle input");
+    let defrag = DiskDefrag::from_str(&puzzle).expect("Unable to parse input");
+    runner.solve("A", || defrag.part_a());
+    runner.solve("B", || defrag.part_b());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    #[test]
-    fn test_test_small_txt() {
-        // 022111222
-        // 2 + 4 + 3 + 4 + 5 + 12 + 14 + 16
-        // 60
-        let puzzle = include_str!("../puzzle/test_small.txt");
-        let defrag = DiskDefrag::from_str(puzzle).unwrap();
-        println!("{:?}", defrag);
-        let result = defrag.part_a();
-        assert_eq!(60, result);
-    }
-
-    #[test]
-    fn test_test_txt_part_a() {
-        let puzzle = include_str!("../puzzle/test.txt");
-        let defrag = DiskDefrag::from_str(puzzle).unwrap();
-        let result = defrag.part_a();
-        assert_eq!(1928, result);
-    }
-
-    #[test]
-    fn test_test_small_rev_txt() {
-        // 000002111
-        // 10 + 6 + 7 + 8
-        // 31
-        let puzzle = include_str!("../puzzle/test_small_rev.txt");
-        let defrag = DiskDefrag::from_str(puzzle).unwrap();
-        let result = defrag.part_b();
-        assert_eq!(31, result);
-    }
-
-    #[test]
-    fn test_test_txt_part_b() {
-        let puzzle = include_str!("../puzzle/test.txt");
-        let defrag = DiskDefrag::from_str(puzzle).unwrap();
-        let result = defrag.part_b();
-        assert_eq!(2858, result);
-    }
+    use util::{
+        example::{self, ExampleErr},
+        output::Answer,
+    };
 
     #[test]
     fn test_acc() {
@@ -196,4 +129,16 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            let defrag = DiskDefrag::from_str(&example.puzzle).unwrap();
+            match part {
+                "A" => Ok(defrag.part_a().render()),
+                "B" => Ok(defrag.part_b().render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
+    }
 }
@@ -1,5 +1,5 @@
 use std::{collections::HashSet, num::ParseIntError, str::FromStr};
-use util::{direction::Direction, point::Point};
+use util::{direction::Direction, point::Point, runner::Runner};
 
 #[derive(Debug)]
 struct TopographicMap {
@@ -111,37 +111,30 @@ impl TopographicMap {
 }
 
 fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
-    let map = TopographicMap::from_str(puzzle).expect("Failed to read map");
-    println!("Part A: {}", map.part_a());
-    println!("Part B: {}", map.part_b());
+    let runner = Runner::from_args();
+    let puzzle = runner.read_input(util::input_path!()).expect("Unable to read puzzle input");
+    let map = TopographicMap::from_str(&puzzle).expect("Failed to read map");
+    runner.solve("A", || map.part_a());
+    runner.solve("B", || map.part_b());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use util::{
+        example::{self, ExampleErr},
+        output::Answer,
+    };
 
     #[test]
-    fn test_test_small_txt() {
-        let puzzle = include_str!("../puzzle/test_small.txt");
-        let map = TopographicMap::from_str(puzzle).unwrap();
-        let result = map.part_a();
-        assert_eq!(1, result);
-    }
-
-    #[test]
-    fn test_test_txt_part_a() {
-        let puzzle = include_str!("../puzzle/test.txt");
-        let map = TopographicMap::from_str(puzzle).unwrap();
-        let result = map.part_a();
-        assert_eq!(36, result);
-    }
-
-    #[test]
-    fn test_test_txt_part_b() {
-        let puzzle = include_str!("../puzzle/test.txt");
-        let map = TopographicMap::from_str(puzzle).unwrap();
-        let result = map.part_b();
-        assert_eq!(81, result);
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            let map = TopographicMap::from_str(&example.puzzle).unwrap();
+            match part {
+                "A" => Ok(map.part_a().render()),
+                "B" => Ok(map.part_b().render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
     }
 }
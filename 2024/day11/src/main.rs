@@ -1,5 +1,7 @@
 use std::{collections::HashMap, num::ParseIntError, str::FromStr};
 
+use util::runner::Runner;
+
 struct PlutoStones {
     stones: Vec<u64>,
 }
@@ -56,7 +58,7 @@ impl PlutoStones {
         stones.values().sum()
     }
 
-    fn part_a(&mut self) -> usize {
+    fn part_a(&self) -> usize {
         let stones = self.stones.clone();
         stones
             .iter()
@@ -64,7 +66,7 @@ impl PlutoStones {
             .sum()
     }
 
-    fn part_b(&mut self) -> usize {
+    fn part_b(&self) -> usize {
         let stones = self.stones.clone();
         stones
             .iter()
@@ -74,10 +76,11 @@ impl PlutoStones {
 }
 
 fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
-    let mut stones = PlutoStones::from_str(puzzle).expect("Could not parse puzzle input!");
-    println!("Part A: {}", stones.part_a());
-    println!("Part B: {}", stones.part_b());
+    let runner = Runner::from_args();
+    let puzzle = runner.read_input(util::input_path!()).expect("Unable to read puzzle input");
+    let stones = PlutoStones::from_str(&puzzle).expect("Could not parse puzzle input!");
+    runner.solve("A", || stones.part_a());
+    runner.solve("B", || stones.part_b());
 }
 
 #[cfg(test)]
@@ -85,11 +88,20 @@ mod tests {
     use std::str::FromStr;
 
     use crate::PlutoStones;
+    use util::{
+        example::{self, ExampleErr},
+        output::Answer,
+    };
 
     #[test]
-    fn test_test_txt() {
-        let puzzle = include_str!("../puzzle/test.txt");
-        let mut stones = PlutoStones::from_str(puzzle).unwrap();
-        assert_eq!(55312, stones.part_a());
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            let stones = PlutoStones::from_str(&example.puzzle).unwrap();
+            match part {
+                "A" => Ok(stones.part_a().render()),
+                "B" => Ok(stones.part_b().render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
     }
 }
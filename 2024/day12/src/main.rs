@@ -4,6 +4,8 @@ use std::{collections::HashMap, str::FromStr};
 
 use util::direction::Direction;
 use util::point::Point;
+use util::runner::Runner;
+use util::union_find;
 
 #[derive(Debug)]
 struct PlantMap {
@@ -17,45 +19,10 @@ impl FromStr for PlantMap {
     fn from_str(puzzle: &str) -> Result<Self, Self::Err> {
         let grid: Vec<Vec<char>> = puzzle.lines().map(|l| l.chars().collect()).collect();
 
-        let in_bounds = |point: &Point| {
-            point.0 >= 0
-                && point.1 >= 0
-                && point.0 < (grid.len() as i32)
-                && point.1 < (grid[0].len() as i32)
-        };
-
         let mut regions: HashMap<char, Vec<Vec<Point>>> = HashMap::new();
-
-        let mut explored = HashSet::new();
-        let mut stack = vec![];
-        let mut current_region = HashSet::new();
-        for (row_idx, row) in grid.iter().enumerate() {
-            for (col_idx, plant_type) in row.iter().enumerate() {
-                let point = Point(row_idx as i32, col_idx as i32);
-                if explored.contains(&point) {
-                    continue;
-                }
-
-                stack.push(point);
-                while let Some(point) = stack.pop() {
-                    explored.insert(point);
-                    current_region.insert(point);
-
-                    // Check in all directions of point
-                    let all_points = Direction::horiz_and_vert()
-                        .into_iter()
-                        .map(|d| point.add(&d.to_point()))
-                        .filter(|p| !explored.contains(p))
-                        .filter(&in_bounds)
-                        .filter(|p| grid[p.0 as usize][p.1 as usize] == *plant_type);
-                    stack.extend(all_points);
-                }
-                regions
-                    .entry(*plant_type)
-                    .or_default()
-                    .push(current_region.clone().into_iter().collect::<Vec<Point>>());
-                current_region.clear();
-            }
+        for region in union_find::grid_components(&grid, |a, b| a == b) {
+            let plant_type = grid[region[0].0 as usize][region[0].1 as usize];
+            regions.entry(plant_type).or_default().push(region);
         }
 
         Ok(PlantMap { regions })
@@ -203,61 +170,30 @@ impl PlantMap {
 }
 
 fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
-    let map = PlantMap::from_str(puzzle).unwrap();
-    println!("Part A: {}", map.part_a());
-    println!("Part B: {}", map.part_b());
+    let runner = Runner::from_args();
+    let puzzle = runner.read_input(util::input_path!()).expect("Unable to read puzzle input");
+    let map = PlantMap::from_str(&puzzle).unwrap();
+    runner.solve("A", || map.part_a());
+    runner.solve("B", || map.part_b());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use util::{
+        example::{self, ExampleErr},
+        output::Answer,
+    };
 
     #[test]
-    fn test_test_1_txt_part_a() {
-        let puzzle = include_str!("../puzzle/test_1.txt");
-        let map = PlantMap::from_str(puzzle).unwrap();
-        let result = map.part_a();
-        assert_eq!(140, result);
-    }
-
-    #[test]
-    fn test_test_2_txt_part_a() {
-        let puzzle = include_str!("../puzzle/test_2.txt");
-        let map = PlantMap::from_str(puzzle).unwrap();
-        let result = map.part_a();
-        assert_eq!(772, result);
-    }
-
-    #[test]
-    fn test_test_3_txt_part_a() {
-        let puzzle = include_str!("../puzzle/test_3.txt");
-        let map = PlantMap::from_str(puzzle).unwrap();
-        let result = map.part_a();
-        assert_eq!(1930, result);
-    }
-
-    #[test]
-    fn test_test_1_txt_part_b() {
-        let puzzle = include_str!("../puzzle/test_1.txt");
-        let map = PlantMap::from_str(puzzle).unwrap();
-        let result = map.part_b();
-        assert_eq!(80, result);
-    }
-
-    #[test]
-    fn test_test_2_txt_part_b() {
-        let puzzle = include_str!("../puzzle/test_2.txt");
-        let map = PlantMap::from_str(puzzle).unwrap();
-        let result = map.part_b();
-        assert_eq!(436, result);
-    }
-
-    #[test]
-    fn test_test_3_txt_part_b() {
-        let puzzle = include_str!("../puzzle/test_3.txt");
-        let map = PlantMap::from_str(puzzle).unwrap();
-        let result = map.part_b();
-        assert_eq!(1206, result);
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            let map = PlantMap::from_str(&example.puzzle).unwrap();
+            match part {
+                "A" => Ok(map.part_a().render()),
+                "B" => Ok(map.part_b().render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
     }
 }
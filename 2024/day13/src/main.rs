@@ -1,5 +1,7 @@
 use std::str::FromStr;
 
+use util::{math, runner::Runner};
+
 #[derive(Debug)]
 struct Button {
     x: i64,
@@ -28,25 +30,66 @@ impl ArcadeMachine {
     }
 
     fn min_tokens(&self, target_adjust: i64) -> Option<i64> {
-        let target_x = self.target_x + target_adjust;
-        let target_y = self.target_y + target_adjust;
-
-        let b_multiplied = self.a.y * target_x - self.a.x * target_y;
-        let b_divisor = self.a.y * self.b.x - self.a.x * self.b.y;
-        let b = b_multiplied / b_divisor;
-        let brem = b_multiplied % b_divisor;
-        let a_multiplied = target_x - b * self.b.x;
-        let a_divisor = self.a.x;
-        let a = a_multiplied / a_divisor;
-        let arem = a_multiplied % a_divisor;
-        if arem != 0 || brem != 0 {
-            return None;
+        let (a, b) = (&self.a, &self.b);
+        let target_x = (self.target_x + target_adjust) as i128;
+        let target_y = (self.target_y + target_adjust) as i128;
+        let cost = |(presses_a, presses_b): (i128, i128)| {
+            i64::try_from(presses_a * a.cost as i128 + presses_b * b.cost as i128).ok()
+        };
+
+        let buttons = [[a.x as i128, b.x as i128], [a.y as i128, b.y as i128]];
+        if let Some((presses_a, presses_b)) = math::solve_2x2(buttons, [target_x, target_y]) {
+            if presses_a < 0 || presses_b < 0 {
+                return None;
+            }
+            return cost((presses_a, presses_b));
+        }
+
+        // A button that does not move the claw is never worth pressing, so the other has to
+        // reach the prize alone
+        let reaches = |(presses_a, presses_b): (i128, i128)| {
+            presses_a >= 0
+                && presses_b >= 0
+                && presses_a * a.x as i128 + presses_b * b.x as i128 == target_x
+                && presses_a * a.y as i128 + presses_b * b.y as i128 == target_y
+        };
+        let alone = |button: &Button| {
+            let (x, y) = (button.x as i128, button.y as i128);
+            match (x, y) {
+                (0, 0) => None,
+                (0, _) => Some(target_y / y),
+                _ => Some(target_x / x),
+            }
+        };
+        let is_zero = |button: &Button| button.x == 0 && button.y == 0;
+        match (is_zero(a), is_zero(b)) {
+            (true, true) => return (target_x == 0 && target_y == 0).then_some(0),
+            (true, false) => return Some((0, alone(b)?)).filter(|&p| reaches(p)).and_then(cost),
+            (false, true) => return Some((alone(a)?, 0)).filter(|&p| reaches(p)).and_then(cost),
+            (false, false) => (),
         }
 
-        Some(a * self.a.cost + b * self.b.cost)
+        // Both buttons move along the same line, so the prize must be on it too. Solve along
+        // whichever axis the buttons move in, and the cheapest is at one end of the valid range.
+        if (a.x * b.y - a.y * b.x) != 0 || target_x * a.y as i128 != target_y * a.x as i128 {
+            return None;
+        }
+        let solutions = match (a.x, b.x) {
+            (0, 0) => math::linear_diophantine(a.y as i128, b.y as i128, target_y)?,
+            _ => math::linear_diophantine(a.x as i128, b.x as i128, target_x)?,
+        };
+        let (low, high) = solutions.non_negative()?;
+        [low, high]
+            .into_iter()
+            .filter_map(|k| solutions.at(k))
+            .filter(|&presses| reaches(presses))
+            .filter_map(cost)
+            .min()
     }
 }
 
+const PART_B_ADJUST: i64 = 10_000_000_000_000;
+
 #[derive(Debug)]
 struct Arcade {
     machines: Vec<ArcadeMachine>,
@@ -70,37 +113,36 @@ impl Arcade {
         self.machines.iter().filter_map(|m| m.min_tokens(0)).sum()
     }
     fn part_b(&self) -> i64 {
-        self.machines.iter().filter_map(|m| m.min_tokens(10_000_000_000_000)).sum()
+        self.machines.iter().filter_map(|m| m.min_tokens(PART_B_ADJUST)).sum()
+    }
+
+    /// The cheapest win for each machine, or `None` where the prize cannot be reached.
+    fn costs(&self, target_adjust: i64) -> Vec<(String, Option<i64>)> {
+        self.machines
+            .iter()
+            .enumerate()
+            .map(|(idx, m)| (format!("machine {}", idx + 1), m.min_tokens(target_adjust)))
+            .collect()
     }
 }
 
 fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
-    let arcade = Arcade::from_str(puzzle).expect("Unable to parse puzzle input");
-    println!("Part A: {}", arcade.part_a());
-    println!("Part B: {}", arcade.part_b());
+    let runner = Runner::from_args();
+    let puzzle = runner.read_input(util::input_path!()).expect("Unable to read puzzle input");
+    let arcade = Arcade::from_str(&puzzle).expect("Unable to parse puzzle input");
+    runner.solve("A", || arcade.part_a());
+    runner.details("A", || arcade.costs(0));
+    runner.solve("B", || arcade.part_b());
+    runner.details("B", || arcade.costs(PART_B_ADJUST));
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-
-    #[test]
-    fn test_test_txt_part_a() {
-        let puzzle = include_str!("../puzzle/test.txt");
-        let arcade = Arcade::from_str(puzzle).unwrap();
-        println!("{:?}", arcade);
-        let result = arcade.part_a();
-        assert_eq!(result, 480);
-    }
-
-    #[test]
-    fn test_test_txt_part_b() {
-        let puzzle = include_str!("../puzzle/test.txt");
-        let arcade = Arcade::from_str(puzzle).unwrap();
-        let result = arcade.part_b();
-        assert_eq!(result, 875318608908);
-    }
+    use util::{
+        example::{self, ExampleErr},
+        output::Answer,
+    };
 
     #[test]
     fn test_min_tokens_single_machine() {
@@ -114,4 +156,53 @@ mod test {
         assert_eq!(machine.min_tokens(1_000_000_000_000), None);
     }
 
+    #[test]
+    fn test_min_tokens_parallel_buttons() {
+        let machine = ArcadeMachine::new_from_strs("2", "2", "1", "1", "10", "10").unwrap();
+        assert_eq!(machine.min_tokens(0), Some(10));
+        let machine = ArcadeMachine::new_from_strs("4", "4", "1", "1", "10", "10").unwrap();
+        assert_eq!(machine.min_tokens(0), Some(8));
+        let machine = ArcadeMachine::new_from_strs("2", "2", "1", "1", "10", "11").unwrap();
+        assert_eq!(machine.min_tokens(0), None);
+        let machine = ArcadeMachine::new_from_strs("2", "2", "4", "4", "5", "5").unwrap();
+        assert_eq!(machine.min_tokens(0), None);
+    }
+
+    #[test]
+    fn test_min_tokens_zero_buttons() {
+        let machine = ArcadeMachine::new_from_strs("0", "0", "1", "2", "3", "5").unwrap();
+        assert_eq!(machine.min_tokens(0), None);
+        let machine = ArcadeMachine::new_from_strs("0", "0", "1", "2", "3", "6").unwrap();
+        assert_eq!(machine.min_tokens(0), Some(3));
+        let machine = ArcadeMachine::new_from_strs("2", "3", "0", "0", "4", "6").unwrap();
+        assert_eq!(machine.min_tokens(0), Some(6));
+        let machine = ArcadeMachine::new_from_strs("0", "0", "0", "0", "0", "0").unwrap();
+        assert_eq!(machine.min_tokens(0), Some(0));
+        let machine = ArcadeMachine::new_from_strs("0", "0", "0", "0", "1", "0").unwrap();
+        assert_eq!(machine.min_tokens(0), None);
+    }
+
+    #[test]
+    fn test_min_tokens_no_x_movement() {
+        let machine = ArcadeMachine::new_from_strs("0", "1", "0", "2", "0", "5").unwrap();
+        assert_eq!(machine.min_tokens(0), Some(5));
+        let machine = ArcadeMachine::new_from_strs("0", "1", "0", "2", "0", "4").unwrap();
+        assert_eq!(machine.min_tokens(0), Some(2));
+        let machine = ArcadeMachine::new_from_strs("0", "1", "0", "2", "3", "5").unwrap();
+        assert_eq!(machine.min_tokens(0), None);
+        let machine = ArcadeMachine::new_from_strs("1", "0", "3", "0", "7", "0").unwrap();
+        assert_eq!(machine.min_tokens(0), Some(5));
+    }
+
+    #[test]
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            let arcade = Arcade::from_str(&example.puzzle).unwrap();
+            match part {
+                "A" => Ok(arcade.part_a().render()),
+                "B" => Ok(arcade.part_b().render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
+    }
 }
@@ -1,12 +1,14 @@
-use std::{
-    cmp::Ordering, collections::{HashMap, HashSet}, fs::File, num::ParseIntError
-};
-use std::{thread, time};
+use std::{cmp::Ordering, collections::HashSet, num::ParseIntError};
 
-use bmp_monochrome::Bmp;
-use util::point::Point;
+use util::{
+    explorer::{Explorer, Frame, Simulation},
+    math,
+    point::Point,
+    runner::Runner,
+    tui,
+};
 
-#[derive(Debug, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
 struct Robot {
     pos: Point,
     vel: Point,
@@ -30,25 +32,21 @@ impl Robot {
         while x < 0 {
             x += width;
         }
-        x = x % width;
+        x %= width;
         while y < 0 {
             y += height;
         }
-        y = y % height;
+        y %= height;
 
         Point(x as i32, y as i32)
     }
 
-    fn tick(&mut self, width: i32, height: i32) {
-        self.pos = self.pos_after(1, width as i64, height as i64);
-    }
-
     fn tick_n(&mut self, steps: usize, width: i32, height: i32) {
         self.pos = self.pos_after(steps as i64, width as i64, height as i64);
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct RestroomSimulation {
     robots: Vec<Robot>,
     width: usize,
@@ -108,89 +106,161 @@ impl RestroomSimulation {
         self.safety_after_steps(100)
     }
 
-    fn tick(&mut self) {
-        self.robots
-            .iter_mut()
-            .for_each(|r| r.tick(self.width as i32, self.height as i32));
-    }
-
     fn tick_n(&mut self, ticks: usize) {
         self.robots
             .iter_mut()
             .for_each(|r| r.tick_n(ticks, self.width as i32, self.height as i32));
     }
 
-    fn to_diagram(&self) -> Result<Bmp, anyhow::Error> {
-        let robot_positions = {
-            let mut robot_positions = HashSet::new();
-            self.robots.iter().for_each(|r| {
-                robot_positions.insert(&r.pos);
-            });
-            robot_positions
-        };
-
-        let mut rows = vec![];
-
-        for col_idx in 0..self.width {
-            let mut row = vec![];
-            for row_idx in 0..self.height {
-                let pos = Point(col_idx as i32, row_idx as i32);
-                row.push(robot_positions.contains(&pos));
-            }
-            rows.push(row);
-        }
+    fn safety(&self) -> usize {
+        let robots = self.robots.iter().map(|r| r.pos).collect::<Vec<_>>();
+        RestroomSimulation::count_in_quadrants(&robots, self.width, self.height)
+    }
 
-        Ok(Bmp::new(rows)?)
+    /// The fraction of robots with another robot in one of the eight cells around them, which
+    /// jumps up when they line up into a picture.
+    fn density(&self) -> f64 {
+        let occupied: HashSet<&Point> = self.robots.iter().map(|r| &r.pos).collect();
+        let neighboured = self
+            .robots
+            .iter()
+            .filter(|r| {
+                (-1..=1)
+                    .flat_map(|dx| (-1..=1).map(move |dy| Point(dx, dy)))
+                    .filter(|offset| *offset != Point(0, 0))
+                    .any(|offset| occupied.contains(&r.pos.add(&offset)))
+            })
+            .count();
+        neighboured as f64 / self.robots.len().max(1) as f64
     }
 
-    fn write_diagram(&self, step_count: usize) -> Result<(), anyhow::Error> {
-        let filename = format!("trees/tree_{}.bmp", step_count);
-        let bmp = self.to_diagram()?;
-        bmp.write(File::create(filename)?)?;
-        Ok(())
+    fn variance(values: impl Iterator<Item = i32> + Clone) -> f64 {
+        let count = values.clone().count() as f64;
+        let mean = values.clone().map(f64::from).sum::<f64>() / count;
+        values.map(|v| (f64::from(v) - mean).powi(2)).sum::<f64>() / count
     }
 
-    fn read_easter_egg() -> Result<Bmp, anyhow::Error> {
-        Ok(Bmp::read(File::open("puzzle/easter_egg.bmp")?)?)
+    fn most_clustered_step(&self, period: usize, axis: fn(&Point) -> i32) -> usize {
+        let spread = |steps: usize| {
+            let positions = self
+                .robots
+                .iter()
+                .map(|r| r.pos_after(steps as i64, self.width as i64, self.height as i64));
+            RestroomSimulation::variance(positions.map(|p| axis(&p)))
+        };
+        (0..period)
+            .min_by(|&l, &r| spread(l).total_cmp(&spread(r)))
+            .unwrap_or(0)
     }
 
-    fn part_b(&mut self) -> usize {
+    fn part_b(&self) -> Option<usize> {
+        // X positions repeat every `width` steps and Y every `height` steps. The tree is where
+        // both axes are at their most bunched up, so pick the tightest step in each period and
+        // combine them.
+        let x_step = self.most_clustered_step(self.width, |p| p.0);
+        let y_step = self.most_clustered_step(self.height, |p| p.1);
+        let (steps, _) = math::crt(&[
+            (x_step as i128, self.width as i128),
+            (y_step as i128, self.height as i128),
+        ])?;
+        usize::try_from(steps).ok()
+    }
+}
 
-        // The following code eventually results in 7083
-        let mut steps = 0;
-        let correct = RestroomSimulation::read_easter_egg().expect("Could not read correct solution");
+impl Simulation for RestroomSimulation {
+    fn step(&mut self) -> bool {
+        self.tick_n(1);
+        true
+    }
 
-        while self.to_diagram().expect("Could not convert to diagram") != correct {
-            steps += 1;
-            self.tick();
+    /// Rows are y and columns x, with each cell showing how many robots stand there.
+    fn frame(&self) -> Frame {
+        let mut frame = Frame::new(self.height, self.width, '.');
+        for robot in &self.robots {
+            let cell = Point(robot.pos.1, robot.pos.0);
+            let count = match frame.get(&cell) {
+                Some('.') => 1,
+                Some(c) => c.to_digit(10).unwrap_or(9) + 1,
+                None => continue,
+            };
+            frame.set(&cell, char::from_digit(count.min(9), 10).unwrap_or('9'));
         }
-        steps
-
-        // Code to write diagrams to disk
-        // loop {
-        //     self.write_diagram(steps).expect("Could not write diagram");
-        //     steps += 1;
-        //     println!("{}", steps);
-        //     self.tick();
-        // }
+        frame
     }
 }
 
 fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
-    let mut sim = RestroomSimulation::from(puzzle, 101, 103).unwrap();
-    println!("Part A: {}", sim.part_a());
-    println!("Part B: {}", sim.part_b());
+    let runner = Runner::from_args();
+    let puzzle = runner.read_input(util::input_path!()).expect("Unable to read puzzle input");
+    let sim = RestroomSimulation::from(&puzzle, 101, 103).unwrap();
+    if runner.explore().is_some() {
+        let explorer = Explorer::new(sim.clone())
+            .with_metric("density", RestroomSimulation::density)
+            .with_metric("safety", |sim: &RestroomSimulation| sim.safety() as f64);
+        tui::explore(explorer).expect("Unable to run the explorer");
+        return;
+    }
+    runner.solve("A", || sim.part_a());
+    runner.solve("B", || sim.part_b());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bmp_monochrome::Bmp;
+    use std::fs::File;
+    use util::{
+        example::{self, ExampleErr},
+        input,
+        output::Answer,
+    };
+
+    /// The robots as a monochrome bitmap, one row per x.
+    fn diagram(sim: &RestroomSimulation) -> Bmp {
+        let robots: HashSet<&Point> = sim.robots.iter().map(|r| &r.pos).collect();
+        let rows = (0..sim.width)
+            .map(|x| {
+                (0..sim.height)
+                    .map(|y| robots.contains(&Point(x as i32, y as i32)))
+                    .collect()
+            })
+            .collect();
+        Bmp::new(rows).unwrap()
+    }
+
     #[test]
-    fn test_test_txt() {
-        let puzzle = include_str!("../puzzle/test.txt");
-        let sim = RestroomSimulation::from(puzzle, 11, 7).unwrap();
-        println!("{:?}", sim);
-        assert_eq!(sim.part_a(), 12);
+    fn test_part_b_matches_easter_egg() {
+        let Some(puzzle) = input::real_input(env!("CARGO_MANIFEST_DIR")) else {
+            return;
+        };
+        let mut sim = RestroomSimulation::from(&puzzle, 101, 103).unwrap();
+        let steps = sim.part_b().unwrap();
+        sim.tick_n(steps);
+        let expected = Bmp::read(File::open("puzzle/easter_egg.bmp").unwrap()).unwrap();
+        assert_eq!(diagram(&sim), expected);
+    }
+
+    #[test]
+    fn test_simulation_frames() {
+        let puzzle = "p=0,0 v=1,0\np=2,1 v=-1,0\np=2,1 v=0,0";
+        let mut sim = RestroomSimulation::from(puzzle, 3, 2).unwrap();
+        assert_eq!(sim.frame().to_string(), "1..\n..2\n");
+        assert_eq!(sim.density(), 0.0);
+        assert!(sim.step());
+        assert_eq!(sim.frame().to_string(), ".1.\n.11\n");
+        assert_eq!(sim.density(), 1.0);
+    }
+
+    #[test]
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            let (width, height) = (example.param("width")?, example.param("height")?);
+            let sim = RestroomSimulation::from(&example.puzzle, width, height).unwrap();
+            match part {
+                "A" => Ok(sim.part_a().render()),
+                "B" => Ok(sim.part_b().render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
     }
 }
@@ -4,32 +4,58 @@ mod warehouse_err;
 mod warehouse;
 mod wide_warehouse;
 use warehouse::Warehouse;
+use util::{explorer::Explorer, runner::Runner, tui};
 use wide_warehouse::WideWarehouse;
 
 fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
-    let mut warehouse = Warehouse::from_str(puzzle).expect("Could not parse warehouse");
-    println!("Part A: {}", warehouse.part_a());
-    let mut wide_warehouse = WideWarehouse::from_str(puzzle).expect("Could not parse wide warehouse");
-    println!("Part B: {}", wide_warehouse.part_b());
+    let runner = Runner::from_args();
+    let puzzle = runner.read_input(util::input_path!()).expect("Unable to read puzzle input");
+    let warehouse = Warehouse::from_str(&puzzle).expect("Could not parse warehouse");
+    let wide_warehouse = WideWarehouse::from_str(&puzzle).expect("Could not parse wide warehouse");
+    if let Some(part) = runner.explore() {
+        let explored = match part {
+            "A" => tui::explore(Explorer::new(warehouse).with_metric("gps", Warehouse::gps)),
+            "B" => {
+                let explorer = Explorer::new(wide_warehouse).with_metric("gps", WideWarehouse::gps);
+                tui::explore(explorer)
+            }
+            _ => panic!("No simulation for part {}", part),
+        };
+        explored.expect("Unable to run the explorer");
+        return;
+    }
+    runner.solve("A", || warehouse.clone().part_a());
+    runner.solve("B", || wide_warehouse.clone().part_b());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use util::{
+        example::{self, ExampleErr},
+        explorer::{Command, Simulation},
+        output::Answer,
+    };
 
     #[test]
-    fn test_test_small_txt() {
+    fn test_explorer_ends_where_part_a_does() {
         let puzzle = include_str!("../puzzle/test_small.txt");
-        let mut warehouse = Warehouse::from_str(puzzle).unwrap();
-        println!("{:?}", warehouse);
-        assert_eq!(2028, warehouse.part_a());
+        let mut explorer = Explorer::new(Warehouse::from_str(puzzle).unwrap());
+        explorer.apply(&Command::Goto(1000)).unwrap();
+        let mut solved = Warehouse::from_str(puzzle).unwrap();
+        solved.part_a();
+        assert_eq!(explorer.end(), Some(explorer.step()));
+        assert_eq!(explorer.frame(), solved.frame());
     }
 
     #[test]
-    fn test_test_large_txt() {
-        let puzzle = include_str!("../puzzle/test_large.txt");
-        let mut warehouse = Warehouse::from_str(puzzle).unwrap();
-        assert_eq!(10092, warehouse.part_a());
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            match part {
+                "A" => Ok(Warehouse::from_str(&example.puzzle).unwrap().part_a().render()),
+                "B" => Ok(WideWarehouse::from_str(&example.puzzle).unwrap().part_b().render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
     }
 }
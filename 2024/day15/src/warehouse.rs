@@ -1,20 +1,22 @@
-use std::{collections::HashMap, str::FromStr};
+use std::str::FromStr;
 
-use util::{direction::Direction, point::Point};
+use tracing::{debug, trace};
+use util::{
+    bitgrid::BitGrid,
+    direction::Direction,
+    explorer::{Frame, Simulation},
+    point::Point,
+};
 
 use crate::warehouse_err::WarehouseErr;
 
-#[derive(Clone, Debug, PartialEq)]
-enum WarehouseCell {
-    Wall,
-    Object,
-}
-
 #[derive(Clone, Debug)]
 pub struct Warehouse {
-    contents: HashMap<Point, WarehouseCell>,
+    walls: BitGrid,
+    objects: BitGrid,
     robot: Point,
     robot_program: Vec<Direction>,
+    next_move: usize,
     width: usize,
     height: usize,
 }
@@ -22,7 +24,6 @@ pub struct Warehouse {
 impl FromStr for Warehouse {
     type Err = WarehouseErr;
     fn from_str(puzzle: &str) -> Result<Self, Self::Err> {
-        let mut contents = HashMap::new();
         let mut robot: Option<Point> = None;
         let mut robot_program = vec![];
 
@@ -32,6 +33,8 @@ impl FromStr for Warehouse {
         }
         let height = grid_lines.len() - 2;
         let width = grid_lines[0].len() - 2;
+        let mut walls = BitGrid::new(height, width);
+        let mut objects = BitGrid::new(height, width);
         for (row_idx, row) in grid_lines.iter().enumerate() {
             if row_idx == 0 || (row_idx == height + 1) {
                 continue;
@@ -43,11 +46,11 @@ impl FromStr for Warehouse {
                 let point = Point((row_idx - 1) as i32, (col_idx - 1) as i32);
                 match cell {
                     '#' => {
-                        contents.insert(point, WarehouseCell::Wall);
+                        walls.insert(&point);
                     },
                     '.' => (),
                     'O' => {
-                        contents.insert(point, WarehouseCell::Object);
+                        objects.insert(&point);
                     },
                     '@' => {
                         robot = Some(point);
@@ -69,7 +72,7 @@ impl FromStr for Warehouse {
             robot_program.push(dir);
         }
 
-        if contents.is_empty() {
+        if walls.is_empty() && objects.is_empty() {
             return Err(WarehouseErr::NoContentsFound);
         }
         if robot.is_none() {
@@ -79,19 +82,24 @@ impl FromStr for Warehouse {
             return Err(WarehouseErr::NoProgramFound);
         }
 
-        Ok(Warehouse { contents, robot: robot.unwrap(), robot_program, width, height })
+        Ok(Warehouse {
+            walls,
+            objects,
+            robot: robot.unwrap(),
+            robot_program,
+            next_move: 0,
+            width,
+            height,
+        })
     }
 }
 
 impl Warehouse {
     fn gps_sum(&self) -> usize {
-        self.contents.iter().filter_map(|(point, cell)| {
-            if *cell != WarehouseCell::Object {
-                return None;
-            }
+        self.objects.iter().map(|point| {
             let y_gps = (point.0 as usize + 1) * 100; 
             let x_gps = point.1 as usize + 1;
-            Some(x_gps + y_gps)
+            x_gps + y_gps
         }).sum()
     }
 
@@ -105,25 +113,21 @@ impl Warehouse {
             return false;
         }
 
-        match self.contents.get(&candidate) {
-            None => {
-                true
-            },
-            Some(WarehouseCell::Object) => {
-                if self.move_object(&candidate, direction) {
-                    if let Some(obj) = self.contents.remove(&candidate) {
-                        self.contents.insert(candidate.add(&direction.to_point()), obj);
-                    }
-                    return true;
-                } else {
-                    return false;
-                }
-            },
-            Some(WarehouseCell::Wall) => false,
+        if self.walls.contains(&candidate) {
+            return false;
+        }
+        if !self.objects.contains(&candidate) {
+            return true;
         }
+        if self.move_object(&candidate, direction) {
+            self.objects.remove(&candidate);
+            self.objects.insert(&candidate.add(&direction.to_point()));
+            return true;
+        }
+        false
     }
 
-    fn print_grid(&self) {
+    fn render(&self) -> String {
         let mut grid = "".to_string();
         for _ in 0..self.width + 2 {
             grid += "#";
@@ -138,10 +142,10 @@ impl Warehouse {
                     grid += "@";
                     continue;
                 }
-                grid += match self.contents.get(&point) {
-                    None => ".",
-                    Some(WarehouseCell::Object) => "O",
-                    Some(WarehouseCell::Wall) => "#",
+                grid += match (self.walls.contains(&point), self.objects.contains(&point)) {
+                    (true, _) => "#",
+                    (_, true) => "O",
+                    _ => ".",
                 };
             }
             grid += "#\n";
@@ -152,36 +156,53 @@ impl Warehouse {
         }
         grid += "\n";
 
-        println!("{}", grid);
+        grid
     }
 
-    pub fn part_a(&mut self) -> usize {
-        let robot_program = self.robot_program.clone();
-        // self.print_grid();
-        for d in robot_program.iter() {
-            let robot_pos_candidate = self.robot.add(&d.to_point());
-            if !self.in_bounds(&robot_pos_candidate) {
-                continue;
-            }
+    fn apply_move(&mut self, d: &Direction) {
+        let robot_pos_candidate = self.robot.add(&d.to_point());
+        if !self.in_bounds(&robot_pos_candidate) {
+            return;
+        }
 
-            match self.contents.get(&robot_pos_candidate) {
-                None => self.robot = robot_pos_candidate,
-                Some(WarehouseCell::Object) => {
-                    if self.move_object(&robot_pos_candidate, d) {
-                        if let Some(obj) = self.contents.remove(&robot_pos_candidate) {
-                            self.contents.insert(robot_pos_candidate.add(&d.to_point()), obj);
-                        }
-                        self.robot = robot_pos_candidate;
-                    }
-                },
-                Some(WarehouseCell::Wall) => (),
-            }
+        if self.walls.contains(&robot_pos_candidate) {
+            return;
+        }
+        if !self.objects.contains(&robot_pos_candidate) {
+            self.robot = robot_pos_candidate;
+        } else if self.move_object(&robot_pos_candidate, d) {
+            self.objects.remove(&robot_pos_candidate);
+            self.objects.insert(&robot_pos_candidate.add(&d.to_point()));
+            self.robot = robot_pos_candidate;
+        }
+    }
 
-            // println!("Direction: {:?}", d);
-            // self.print_grid();
-            // println!("");
+    pub fn gps(&self) -> f64 {
+        self.gps_sum() as f64
+    }
+
+    pub fn part_a(&mut self) -> usize {
+        debug!(width = self.width, height = self.height, "initial grid\n{}", self.render());
+        while self.step() {
+            trace!(robot = ?self.robot, "\n{}", self.render());
         }
+        debug!("final grid\n{}", self.render());
 
         self.gps_sum()
     }
 }
+
+impl Simulation for Warehouse {
+    fn step(&mut self) -> bool {
+        let Some(d) = self.robot_program.get(self.next_move).copied() else {
+            return false;
+        };
+        self.apply_move(&d);
+        self.next_move += 1;
+        true
+    }
+
+    fn frame(&self) -> Frame {
+        Frame::from(self.render().as_str())
+    }
+}
@@ -2,7 +2,12 @@ use std::{
     collections::{HashMap, HashSet},
     str::FromStr,
 };
-use util::{direction::Direction, point::Point};
+use tracing::{debug, trace};
+use util::{
+    direction::Direction,
+    explorer::{Frame, Simulation},
+    point::Point,
+};
 
 use crate::warehouse_err::{WarehouseErr, WarehouseMoveErr};
 
@@ -19,6 +24,7 @@ pub struct WideWarehouse {
     contents: HashMap<Point, WarehouseCell>,
     robot_pos: Point,
     robot_program: Vec<Direction>,
+    next_move: usize,
     width: usize,
     height: usize,
 }
@@ -95,6 +101,7 @@ impl FromStr for WideWarehouse {
             contents,
             robot_pos: robot_pos.unwrap(),
             robot_program,
+            next_move: 0,
             width,
             height,
         })
@@ -102,7 +109,7 @@ impl FromStr for WideWarehouse {
 }
 
 impl WideWarehouse {
-    fn print_grid(&self) {
+    fn render(&self) -> String {
         let mut grid = "".to_string();
         for _ in 0..self.width + 4 {
             grid += "#";
@@ -129,7 +136,7 @@ impl WideWarehouse {
         }
         grid += "\n";
 
-        println!("{}", grid);
+        grid
     }
 
     fn gps_sum(&self) -> usize {
@@ -169,7 +176,7 @@ impl WideWarehouse {
             if visited.contains(point) {
                 return vec![];
             }
-            let current= self.contents.get(&point);
+            let current= self.contents.get(point);
             let mut stack = vec![];
             visited.insert(*point);
             
@@ -177,7 +184,7 @@ impl WideWarehouse {
                 None => stack.push(Ok((*point, *candidate))),
                 Some(_) => {
                     stack.push(Ok((*point, *candidate)));
-                    stack.append(&mut self.build_move_stack(&candidate, direction, visited));
+                    stack.append(&mut self.build_move_stack(candidate, direction, visited));
                 }
             }
 
@@ -192,7 +199,7 @@ impl WideWarehouse {
             stack
         };
 
-        match (self.contents.get(&point), self.contents.get(&candidate)) {
+        match (self.contents.get(point), self.contents.get(&candidate)) {
             // I should never be empty or a wall at the current point, or be anything moving into the robot.
             (None, _) | (Some(WarehouseCell::Wall), _) | (_, Some(WarehouseCell::Robot)) => {
                 vec![Err(WarehouseMoveErr::InvalidPointMoved)]
@@ -245,16 +252,37 @@ impl WideWarehouse {
         Ok(())
     }
 
+    pub fn gps(&self) -> f64 {
+        self.gps_sum() as f64
+    }
+
     pub fn part_b(&mut self) -> usize {
-        let robot_program = self.robot_program.clone();
-        for d in robot_program.iter() {
-            let _ = self.move_robot_and_boxes(d);
-        }
+        debug!(width = self.width, height = self.height, "initial grid\n{}", self.render());
+        while self.step() {}
+        debug!("final grid\n{}", self.render());
 
         self.gps_sum()
     }
 }
 
+impl Simulation for WideWarehouse {
+    fn step(&mut self) -> bool {
+        let Some(d) = self.robot_program.get(self.next_move).copied() else {
+            return false;
+        };
+        self.next_move += 1;
+        match self.move_robot_and_boxes(&d) {
+            Ok(()) => trace!(direction = ?d, robot = ?self.robot_pos, "\n{}", self.render()),
+            Err(err) => trace!(direction = ?d, ?err, "move blocked"),
+        }
+        true
+    }
+
+    fn frame(&self) -> Frame {
+        Frame::from(self.render().as_str())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -263,7 +291,7 @@ mod tests {
     fn test_test_small_txt() {
         let puzzle = include_str!("../puzzle/test_small_wide.txt");
         let mut warehouse = WideWarehouse::from_str(puzzle).unwrap();
-        warehouse.print_grid();
+        println!("{}", warehouse.render());
         assert_eq!(105, warehouse.part_b());
         assert!(false);
     }
@@ -272,7 +300,7 @@ mod tests {
     fn test_test_large_txt() {
         let puzzle = include_str!("../puzzle/test_large.txt");
         let mut warehouse = WideWarehouse::from_str(puzzle).unwrap();
-        warehouse.print_grid();
+        println!("{}", warehouse.render());
         println!("{:?}", warehouse);
         assert_eq!(9021, warehouse.part_b());
     }
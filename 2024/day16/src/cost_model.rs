@@ -0,0 +1,197 @@
+//! What it costs the reindeer to move and turn, and which ways it starts and must finish facing.
+//!
+//! The turn cost is for a quarter turn, and once diagonal moves are allowed an eighth turn costs
+//! half that, rounded up. A U-turn rotates the reindeer straight round in one go, and is only
+//! taken where it is cheaper than the turns it replaces.
+
+use util::{direction::Direction, point::Point};
+
+#[derive(Debug, PartialEq)]
+pub enum CostModelErr {
+    /// A move that costs nothing, which would let tied routes loop forever.
+    ZeroCost(&'static str),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CostModel {
+    step: usize,
+    turn: usize,
+    u_turn: usize,
+    diagonals: bool,
+    start_facing: Direction,
+    end_facing: Option<Direction>,
+}
+
+impl Default for CostModel {
+    /// The puzzle's own rules: steps cost 1 and quarter turns 1000, and the reindeer starts facing
+    /// east and may finish facing any way.
+    fn default() -> Self {
+        CostModel {
+            step: 1,
+            turn: 1000,
+            u_turn: 2000,
+            diagonals: false,
+            start_facing: Direction::Right,
+            end_facing: None,
+        }
+    }
+}
+
+impl CostModel {
+    pub fn with_step(mut self, step: usize) -> Result<CostModel, CostModelErr> {
+        if step == 0 {
+            return Err(CostModelErr::ZeroCost("step"));
+        }
+        self.step = step;
+        Ok(self)
+    }
+
+    pub fn with_turn(mut self, turn: usize) -> Result<CostModel, CostModelErr> {
+        if turn == 0 {
+            return Err(CostModelErr::ZeroCost("turn"));
+        }
+        self.turn = turn;
+        Ok(self)
+    }
+
+    pub fn with_u_turn(mut self, u_turn: usize) -> Result<CostModel, CostModelErr> {
+        if u_turn == 0 {
+            return Err(CostModelErr::ZeroCost("u_turn"));
+        }
+        self.u_turn = u_turn;
+        Ok(self)
+    }
+
+    /// Lets the reindeer face and move diagonally, a step costing the same either way.
+    pub fn with_diagonals(mut self) -> CostModel {
+        self.diagonals = true;
+        self
+    }
+
+    pub fn with_start_facing(mut self, facing: Direction) -> CostModel {
+        self.start_facing = facing;
+        self
+    }
+
+    pub fn with_end_facing(mut self, facing: Direction) -> CostModel {
+        self.end_facing = Some(facing);
+        self
+    }
+
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    pub fn diagonals(&self) -> bool {
+        self.diagonals
+    }
+
+    pub fn start_facing(&self) -> Direction {
+        self.start_facing
+    }
+
+    /// Every way the reindeer can face, and so move.
+    pub fn facings(&self) -> Vec<Direction> {
+        match self.diagonals {
+            true => Direction::all_directions(),
+            false => Direction::horiz_and_vert(),
+        }
+    }
+
+    /// The cheapest series of turns, U-turns included, from one facing to another.
+    pub fn rotation(&self, from: Direction, to: Direction) -> usize {
+        let eighth = |dir: Direction| {
+            let all = Direction::all_directions();
+            all.iter().position(|other| *other == dir).unwrap_or(0)
+        };
+        let apart = (eighth(from) + 8 - eighth(to)) % 8;
+        let eighths = apart.min(8 - apart);
+        let turning = |eighths: usize| (eighths * self.turn).div_ceil(2);
+        turning(eighths).min(self.u_turn + turning(4 - eighths))
+    }
+
+    /// Whether the reindeer may finish facing this way.
+    pub fn finishes(&self, facing: Direction) -> bool {
+        self.end_facing.is_none_or(|end| end == facing)
+    }
+
+    /// What a path costs, turning to face along each straight run before moving down it and
+    /// turning to the required end facing at the end. `None` if the path is empty or has a run
+    /// the reindeer cannot face along.
+    pub fn path_cost(&self, visited: &[Point]) -> Option<usize> {
+        let mut facing = self.start_facing;
+        let mut cost = 0;
+        for pair in visited.windows(2) {
+            let diff = pair[0].diff(&pair[1]);
+            let (rows, cols) = (diff.0.unsigned_abs(), diff.1.unsigned_abs());
+            if rows != 0 && cols != 0 && rows != cols {
+                return None;
+            }
+            let dir = Direction::from_point(&diff.normalize())?;
+            if !self.facings().contains(&dir) {
+                return None;
+            }
+            cost += self.rotation(facing, dir) + rows.max(cols) as usize * self.step;
+            facing = dir;
+        }
+        let finish = self.end_facing.map_or(0, |end| self.rotation(facing, end));
+        visited.first().map(|_| cost + finish)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation() {
+        let model = CostModel::default();
+        assert_eq!(model.rotation(Direction::Right, Direction::Right), 0);
+        assert_eq!(model.rotation(Direction::Right, Direction::Up), 1000);
+        assert_eq!(model.rotation(Direction::Right, Direction::Left), 2000);
+
+        let model = CostModel::default().with_u_turn(10).unwrap();
+        assert_eq!(model.rotation(Direction::Up, Direction::Down), 10);
+        assert_eq!(model.rotation(Direction::Up, Direction::Left), 1000);
+
+        let model = CostModel::default().with_diagonals().with_turn(3).unwrap();
+        let model = model.with_u_turn(5).unwrap();
+        assert_eq!(model.rotation(Direction::Up, Direction::UpRight), 2);
+        assert_eq!(model.rotation(Direction::Up, Direction::Right), 3);
+        assert_eq!(model.rotation(Direction::Up, Direction::DownRight), 5);
+        assert_eq!(model.rotation(Direction::Up, Direction::Down), 5);
+        let model = model.with_u_turn(1).unwrap();
+        assert_eq!(model.rotation(Direction::Up, Direction::DownRight), 3);
+    }
+
+    #[test]
+    fn test_zero_costs() {
+        let model = CostModel::default();
+        assert_eq!(
+            model.clone().with_step(0),
+            Err(CostModelErr::ZeroCost("step"))
+        );
+        assert_eq!(
+            model.clone().with_turn(0),
+            Err(CostModelErr::ZeroCost("turn"))
+        );
+        assert_eq!(model.with_u_turn(0), Err(CostModelErr::ZeroCost("u_turn")));
+    }
+
+    #[test]
+    fn test_path_cost() {
+        let path = [Point(3, 1), Point(3, 3), Point(1, 3)];
+        let model = CostModel::default();
+        assert_eq!(model.path_cost(&path), Some(1004));
+        assert_eq!(model.path_cost(&[]), None);
+        assert_eq!(model.path_cost(&[Point(3, 1)]), Some(0));
+        assert_eq!(model.path_cost(&[Point(3, 1), Point(1, 3)]), None);
+
+        let model = model
+            .with_start_facing(Direction::Left)
+            .with_end_facing(Direction::Down);
+        assert_eq!(model.path_cost(&path), Some(2000 + 1000 + 2000 + 4));
+        let model = CostModel::default().with_diagonals().with_step(10).unwrap();
+        assert_eq!(model.path_cost(&[Point(3, 1), Point(1, 3)]), Some(520));
+    }
+}
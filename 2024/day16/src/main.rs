@@ -1,18 +1,136 @@
 use std::str::FromStr;
 
+mod cost_model;
 mod maze_cell;
 mod reindeer_graph;
 mod reindeer_junction_maze;
 mod reindeer_maze;
 mod reindeer_maze_err;
 
+use cost_model::{CostModel, CostModelErr};
 use reindeer_graph::ReindeerGraph;
-// use reindeer_junction_maze::ReindeerJunctionMaze;
-// use reindeer_maze::ReindeerMaze;
+use reindeer_junction_maze::ReindeerJunctionMaze;
+use reindeer_maze::ReindeerMaze;
+use util::{direction::Direction, runner::Runner, strategy::Strategies};
+
+// The maze and junction searches enumerate every path, so they only finish on the examples.
+fn part_a_strategies(puzzle: &str) -> Strategies<'_, Option<usize>> {
+    Strategies::new("A")
+        .register("graph", move || ReindeerGraph::from_str(puzzle).ok()?.part_a())
+        .register_slow("junction", move || {
+            ReindeerJunctionMaze::from_str(puzzle).ok()?.part_a()
+        })
+        .register_slow("maze", move || ReindeerMaze::from_str(puzzle).ok()?.part_a())
+}
+
+fn part_b_strategies(puzzle: &str) -> Strategies<'_, Option<usize>> {
+    Strategies::new("B").register("graph", move || ReindeerGraph::from_str(puzzle).ok()?.part_b())
+}
+
+/// Other rules for the maze, to see how they change the cheapest route.
+fn variants() -> Result<Vec<(&'static str, CostModel)>, CostModelErr> {
+    Ok(vec![
+        ("cheap turns", CostModel::default().with_turn(1)?),
+        ("costly steps", CostModel::default().with_step(1000)?),
+        ("cheap U-turns", CostModel::default().with_u_turn(1)?),
+        ("diagonals", CostModel::default().with_diagonals()),
+        ("start facing north", CostModel::default().with_start_facing(Direction::Up)),
+        ("end facing south", CostModel::default().with_end_facing(Direction::Down)),
+    ])
+}
 
 fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
-    let maze = ReindeerGraph::from_str(puzzle).expect("Could not parse maze");
-    println!("Part A: {:?}", maze.part_a());
-    println!("Part B: {:?}", maze.part_b());
+    let runner = Runner::from_args();
+    let puzzle = runner.read_input(util::input_path!()).expect("Unable to read puzzle input");
+    runner.run(&part_a_strategies(&puzzle));
+    runner.details("A", || {
+        variants()
+            .expect("Bad cost model")
+            .into_iter()
+            .map(|(name, model)| {
+                let graph = ReindeerGraph::new(&puzzle, model).ok();
+                (name.to_string(), graph.and_then(|graph| graph.part_a()))
+            })
+            .collect::<Vec<_>>()
+    });
+    runner.run(&part_b_strategies(&puzzle));
+    runner.details("B", || {
+        let graph = ReindeerGraph::from_str(&puzzle).ok();
+        let Some(routes) = graph.and_then(|graph| graph.optimal_routes().ok()) else {
+            return vec![];
+        };
+        vec![
+            ("cost".to_string(), routes.cost as u128),
+            ("routes".to_string(), routes.routes),
+            ("tiles".to_string(), routes.tiles.len() as u128),
+        ]
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use util::{
+        example::{self, ExampleErr},
+        output::Answer,
+    };
+
+    #[test]
+    fn test_strategies_agree_on_examples() {
+        let examples = [
+            include_str!("../puzzle/test_very_small.txt"),
+            include_str!("../puzzle/test_small.txt"),
+            include_str!("../puzzle/test_large.txt"),
+        ];
+        for puzzle in examples {
+            assert!(part_a_strategies(puzzle).cross_check().is_ok());
+            assert!(part_b_strategies(puzzle).cross_check().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_solvers_agree_on_variants() {
+        let examples = [
+            include_str!("../puzzle/test_very_small.txt"),
+            include_str!("../puzzle/test_smaller.txt"),
+        ];
+        for puzzle in examples {
+            for (name, model) in variants().unwrap() {
+                let graph = ReindeerGraph::new(puzzle, model.clone()).unwrap().part_a();
+                let junction = ReindeerJunctionMaze::new(puzzle, model.clone()).unwrap().part_a();
+                let maze = ReindeerMaze::new(puzzle, model).unwrap().part_a();
+                assert_eq!((name, graph), (name, junction));
+                assert_eq!((name, graph), (name, maze));
+            }
+        }
+    }
+
+    #[test]
+    fn test_optimal_routes_on_variants() {
+        let examples = [
+            include_str!("../puzzle/test_very_small.txt"),
+            include_str!("../puzzle/test_smaller.txt"),
+            include_str!("../puzzle/test_small.txt"),
+            include_str!("../puzzle/test_large.txt"),
+            include_str!("../puzzle/test_tied_routes.txt"),
+        ];
+        for puzzle in examples {
+            for (name, model) in variants().unwrap() {
+                let graph = ReindeerGraph::new(puzzle, model).unwrap();
+                let routes = graph.optimal_routes().map(|routes| routes.cost);
+                assert_eq!((name, routes.ok()), (name, graph.part_a()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            match part {
+                "A" => Ok(part_a_strategies(&example.puzzle).run_default().render()),
+                "B" => Ok(part_b_strategies(&example.puzzle).run_default().render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
+    }
 }
@@ -1,26 +1,52 @@
 use std::{
-    collections::{HashMap, HashSet},
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
     str::FromStr,
 };
 
-use pathfinding::{directed::yen::yen, prelude::dijkstra};
+use pathfinding::prelude::dijkstra;
 use util::{direction::Direction, point::Point};
 
-use crate::{maze_cell::MazeCell, reindeer_maze_err::ReindeerMazeErr};
+use crate::{cost_model::CostModel, maze_cell::MazeCell, reindeer_maze_err::ReindeerMazeErr};
 
 type FacingPoint = (Point, Direction);
 type ReachableNodeWithCost = (FacingPoint, usize);
 
+/// Every cheapest route from the start to the end, however many tie.
+#[derive(Debug, PartialEq)]
+pub struct OptimalRoutes {
+    pub cost: usize,
+    /// Every tile on at least one of the routes.
+    pub tiles: HashSet<Point>,
+    /// How many distinct sequences of moves and turns reach the end at that cost.
+    pub routes: u128,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RoutesErr {
+    NoRoute,
+    /// Nodes on the cheapest routes that are each other's predecessors, which only zero-cost
+    /// moves allow, so the routes could loop among them forever.
+    Cycle,
+}
+
 #[derive(Debug)]
 pub struct ReindeerGraph {
     graph: HashMap<FacingPoint, Vec<ReachableNodeWithCost>>,
     start: Point,
     end: Point,
+    model: CostModel,
 }
 
 impl FromStr for ReindeerGraph {
     type Err = ReindeerMazeErr;
     fn from_str(puzzle: &str) -> Result<Self, Self::Err> {
+        ReindeerGraph::new(puzzle, CostModel::default())
+    }
+}
+
+impl ReindeerGraph {
+    pub fn new(puzzle: &str, model: CostModel) -> Result<Self, ReindeerMazeErr> {
         // Get a grid of all the points
         let mut end_pos = None;
         let mut start_pos = None;
@@ -81,7 +107,10 @@ impl FromStr for ReindeerGraph {
                         None
                     })
                     .collect();
-                if neighbours.len() != 2 {
+                // Diagonal moves can leave a corridor anywhere, and the reindeer has to be able
+                // to turn at the start and the end
+                let is_start_or_end = matches!(cell, MazeCell::Start | MazeCell::End);
+                if model.diagonals() || is_start_or_end || neighbours.len() != 2 {
                     junctions.insert(point);
                     continue;
                 }
@@ -106,37 +135,46 @@ impl FromStr for ReindeerGraph {
                     continue;
                 }
 
-                for dir in Direction::horiz_and_vert() {
+                // The next junction along each facing, and how many steps away it is
+                let runs: Vec<(Direction, Point, usize)> = model
+                    .facings()
+                    .into_iter()
+                    .filter_map(|dir| {
+                        let mut next = point.add(&dir.to_point());
+                        // If a wall or the end, then stop there
+                        match &grid[next.0 as usize][next.1 as usize] {
+                            MazeCell::Wall | MazeCell::Start => None,
+                            MazeCell::End => Some((dir, next, 1)),
+                            MazeCell::Empty => {
+                                let mut acc = 1;
+                                while !junctions.contains(&next) {
+                                    next = next.add(&dir.to_point());
+                                    acc += 1;
+                                }
+                                Some((dir, next, acc))
+                            }
+                        }
+                    })
+                    .collect();
+
+                for dir in model.facings() {
                     let facing_point = (point, dir);
                     let mut reachable = vec![];
 
-                    // Sanity check that left and right are not walls
-                    let right_point = point.add(&dir.right90().to_point());
-                    let right_contents = &grid[right_point.0 as usize][right_point.1 as usize];
-                    if *right_contents != MazeCell::Wall {
-                        reachable.push(((point, dir.right90()), 1000));
-                    }
-                    let left_point = point.add(&dir.left90().to_point());
-                    let left_contents = &grid[left_point.0 as usize][left_point.1 as usize];
-                    if *left_contents != MazeCell::Wall {
-                        reachable.push(((point, dir.left90()), 1000));
-                    }
-
-                    let mut next = point.add(&dir.to_point());
-                    // If a wall or the end, then stop there
-                    let next_cell = &grid[next.0 as usize][next.1 as usize];
-                    match next_cell {
-                        MazeCell::Wall | MazeCell::Start => (),
-                        MazeCell::End => {reachable.push(((next, dir), 1));},
-                        MazeCell::Empty => {
-                            let mut acc = 1;
-                            while !junctions.contains(&next) {
-                                next = next.add(&dir.to_point());
-                                acc += 1;
+                    // Each turn is taken in one go and followed by a move, so turning twice in a
+                    // row can never tie with the single turn it adds up to. The exception is a
+                    // final turn at the end, to the facing the reindeer must finish in.
+                    if *cell == MazeCell::End && !model.finishes(dir) {
+                        for other in model.facings() {
+                            if model.finishes(other) {
+                                reachable.push(((point, other), model.rotation(dir, other)));
                             }
-                            reachable.push(((next, dir), acc));
                         }
                     }
+                    for &(other, next, steps) in &runs {
+                        let cost = model.rotation(dir, other) + steps * model.step();
+                        reachable.push(((next, other), cost));
+                    }
                     graph.insert(facing_point, reachable);
                 }
             }
@@ -146,13 +184,12 @@ impl FromStr for ReindeerGraph {
             graph,
             start: start_pos,
             end: end_pos,
+            model,
         })
     }
-}
 
-impl ReindeerGraph {
     fn successors(&self, node: &FacingPoint) -> Vec<ReachableNodeWithCost> {
-        self.graph.get(node).unwrap().to_vec()
+        self.graph.get(node).cloned().unwrap_or_default()
     }
 
     fn expand(path: &[FacingPoint]) -> Vec<Point> {
@@ -161,14 +198,14 @@ impl ReindeerGraph {
             return expanded;
         }
 
-        let (mut prev_point, mut dir) = path.first().expect("Empty visited path");
+        // Each node is reached by turning to its facing and then moving along it
+        let (mut prev_point, _) = path.first().expect("Empty visited path");
         expanded.push(prev_point);
-        for (next_point, next_dir) in path.iter().skip(1) {
+        for (next_point, dir) in path.iter().skip(1) {
             while prev_point != *next_point {
                 prev_point = prev_point.add(&dir.to_point());
                 expanded.push(prev_point);
             }
-            dir = *next_dir;
         }
 
         expanded
@@ -176,9 +213,9 @@ impl ReindeerGraph {
 
     pub fn part_a(&self) -> Option<usize> {
         let paths = dijkstra(
-            &(self.start, Direction::Right),
+            &(self.start, self.model.start_facing()),
             |p| self.successors(p),
-            |&p| p.0 == self.end
+            |p| self.is_goal(p),
         );
         paths
             .iter()
@@ -187,38 +224,147 @@ impl ReindeerGraph {
             .min()
     }
 
-    pub fn part_b(&self) -> Option<usize> {
-        let mut shortest_paths: Option<Vec<(Vec<FacingPoint>, usize)>> = None;
-        for path_count in [5, 10, 20, 30, 40, 50] {
-            let paths = yen(
-                &(self.start, Direction::Right),
-                |p| self.successors(p),
-                |&p| p.0 == self.end,
-                path_count,
-            );
-            if paths.is_empty() {
-                return None;
+    /// Whether the reindeer has finished on reaching this node.
+    fn is_goal(&self, node: &FacingPoint) -> bool {
+        node.0 == self.end && self.model.finishes(node.1)
+    }
+
+    /// Dijkstra from the start, keeping every predecessor a node can be reached
+    /// from at its lowest cost rather than only the first found. Nodes costing more than the
+    /// cheapest way to the end are never expanded.
+    fn cheapest_predecessors(
+        &self,
+    ) -> (
+        HashMap<FacingPoint, usize>,
+        HashMap<FacingPoint, Vec<FacingPoint>>,
+    ) {
+        let start = (self.start, self.model.start_facing());
+        let mut costs = HashMap::from([(start, 0)]);
+        let mut predecessors: HashMap<FacingPoint, Vec<FacingPoint>> = HashMap::new();
+        let mut queued = vec![start];
+        let mut heap = BinaryHeap::from([(Reverse(0), 0)]);
+        let mut end_cost = None;
+        while let Some((Reverse(cost), idx)) = heap.pop() {
+            let node = queued[idx];
+            if costs[&node] < cost || end_cost.is_some_and(|end_cost| cost > end_cost) {
+                continue;
+            }
+            if self.is_goal(&node) {
+                end_cost.get_or_insert(cost);
+            }
+            for &(next, step) in self.graph.get(&node).into_iter().flatten() {
+                let next_cost = cost + step;
+                match costs.get(&next) {
+                    Some(&best) if best < next_cost => continue,
+                    Some(&best) if best == next_cost => {
+                        predecessors.entry(next).or_default().push(node);
+                        continue;
+                    }
+                    _ => (),
+                }
+                costs.insert(next, next_cost);
+                predecessors.insert(next, vec![node]);
+                queued.push(next);
+                heap.push((Reverse(next_cost), queued.len() - 1));
             }
-            let shortest_cost = paths[0].1;
-            let only_shortest: Vec<_> = paths
-                .iter()
-                .take_while(|(_, cost)| *cost == shortest_cost)
-                .map(|(v, c)| (v.to_vec(), *c))
-                .collect();
-            if only_shortest.len() != paths.len() {
-                shortest_paths = Some(only_shortest);
-                break;
+        }
+        (costs, predecessors)
+    }
+
+    /// Counts the routes to each node as the sum over its predecessors, visiting the nodes in
+    /// topological order so every predecessor has been counted first.
+    fn count_routes(
+        nodes: &[FacingPoint],
+        predecessors: &HashMap<FacingPoint, Vec<FacingPoint>>,
+    ) -> Result<HashMap<FacingPoint, u128>, RoutesErr> {
+        let preds = |node: &FacingPoint| predecessors.get(node).into_iter().flatten();
+        let mut waiting: HashMap<FacingPoint, usize> = HashMap::new();
+        let mut successors: HashMap<FacingPoint, Vec<FacingPoint>> = HashMap::new();
+        for node in nodes {
+            waiting.insert(*node, preds(node).count());
+            for prev in preds(node) {
+                successors.entry(*prev).or_default().push(*node);
             }
         }
 
-        let shortest_paths = shortest_paths.as_ref()?;
+        let mut ready: Vec<FacingPoint> = nodes
+            .iter()
+            .filter(|node| waiting[*node] == 0)
+            .copied()
+            .collect();
+        let mut routes = HashMap::new();
+        while let Some(node) = ready.pop() {
+            let count = match predecessors.get(&node) {
+                Some(prevs) => prevs
+                    .iter()
+                    .map(|prev| routes.get(prev).copied().ok_or(RoutesErr::Cycle))
+                    .sum::<Result<u128, RoutesErr>>()?,
+                None => 1,
+            };
+            routes.insert(node, count);
+            for next in successors.get(&node).into_iter().flatten() {
+                let left = waiting.get_mut(next).ok_or(RoutesErr::Cycle)?;
+                *left -= 1;
+                if *left == 0 {
+                    ready.push(*next);
+                }
+            }
+        }
+        match routes.len() == nodes.len() {
+            true => Ok(routes),
+            false => Err(RoutesErr::Cycle),
+        }
+    }
+
+    /// Walks back from every end orientation reached at the lowest cost, through every
+    /// predecessor, so each tied route is included however many there are.
+    pub fn optimal_routes(&self) -> Result<OptimalRoutes, RoutesErr> {
+        let (costs, predecessors) = self.cheapest_predecessors();
+        let ends: Vec<(FacingPoint, usize)> = self
+            .model
+            .facings()
+            .into_iter()
+            .map(|dir| (self.end, dir))
+            .filter(|node| self.is_goal(node))
+            .filter_map(|node| Some((node, *costs.get(&node)?)))
+            .collect();
+        let cost = ends
+            .iter()
+            .map(|(_, cost)| *cost)
+            .min()
+            .ok_or(RoutesErr::NoRoute)?;
+        let ends: Vec<FacingPoint> = ends
+            .into_iter()
+            .filter(|(_, end_cost)| *end_cost == cost)
+            .map(|(node, _)| node)
+            .collect();
 
-        let mut tiles_traversed: HashSet<Point> = HashSet::new();
-        for (path, _) in shortest_paths {
-            tiles_traversed.extend(ReindeerGraph::expand(path));
+        let mut on_route = ends.clone();
+        let mut seen: HashSet<FacingPoint> = on_route.iter().copied().collect();
+        let mut tiles = HashSet::new();
+        let mut idx = 0;
+        while let Some(&node) = on_route.get(idx) {
+            idx += 1;
+            tiles.insert(node.0);
+            for &prev in predecessors.get(&node).into_iter().flatten() {
+                tiles.extend(ReindeerGraph::expand(&[prev, node]));
+                if seen.insert(prev) {
+                    on_route.push(prev);
+                }
+            }
         }
 
-        Some(tiles_traversed.len())
+        let routes = ReindeerGraph::count_routes(&on_route, &predecessors)?;
+        let routes = ends.iter().filter_map(|end| routes.get(end)).sum();
+        Ok(OptimalRoutes {
+            cost,
+            tiles,
+            routes,
+        })
+    }
+
+    pub fn part_b(&self) -> Option<usize> {
+        Some(self.optimal_routes().ok()?.tiles.len())
     }
 }
 
@@ -226,45 +372,75 @@ impl ReindeerGraph {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_test_very_small_txt() {
-        let puzzle = include_str!("../puzzle/test_very_small.txt");
-        let maze = ReindeerGraph::from_str(puzzle).unwrap();
-        assert_eq!(maze.part_a(), Some(2003));
+    fn optimal_routes(puzzle: &str) -> OptimalRoutes {
+        let graph = ReindeerGraph::from_str(puzzle).unwrap();
+        graph.optimal_routes().unwrap()
     }
 
     #[test]
-    fn test_test_smaller_txt() {
-        let puzzle = include_str!("../puzzle/test_smaller.txt");
-        let maze = ReindeerGraph::from_str(puzzle).unwrap();
-        assert_eq!(maze.part_a(), Some(2005));
+    fn test_optimal_routes() {
+        let routes = optimal_routes(include_str!("../puzzle/test_small.txt"));
+        assert_eq!(
+            (routes.cost, routes.tiles.len(), routes.routes),
+            (7036, 45, 3)
+        );
+        let routes = optimal_routes(include_str!("../puzzle/test_large.txt"));
+        assert_eq!(
+            (routes.cost, routes.tiles.len(), routes.routes),
+            (11048, 64, 2)
+        );
     }
 
     #[test]
-    fn test_test_small_txt_part_a() {
-        let puzzle = include_str!("../puzzle/test_small.txt");
-        let maze = ReindeerGraph::from_str(puzzle).unwrap();
-        assert_eq!(maze.part_a(), Some(7036));
+    fn test_many_tied_routes() {
+        // Six loops in a row, each passable over or under at the same cost.
+        let routes = optimal_routes(include_str!("../puzzle/test_tied_routes.txt"));
+        assert_eq!(routes.cost, 24036);
+        assert_eq!(routes.routes, 64);
+        assert_eq!(routes.tiles.len(), 55);
+        assert!(routes.tiles.contains(&Point(1, 2)) && routes.tiles.contains(&Point(3, 24)));
     }
 
     #[test]
-    fn test_test_large_txt_part_a() {
-        let puzzle = include_str!("../puzzle/test_large.txt");
-        let maze = ReindeerGraph::from_str(puzzle).unwrap();
-        assert_eq!(maze.part_a(), Some(11048));
+    fn test_cost_model() {
+        let puzzle = include_str!("../puzzle/test_smaller.txt");
+        let cost = |model| ReindeerGraph::new(puzzle, model).unwrap().part_a();
+        assert_eq!(cost(CostModel::default()), Some(2005));
+        let cheap_turns = CostModel::default().with_turn(1).unwrap();
+        assert_eq!(cost(cheap_turns.with_step(10).unwrap()), Some(52));
+        assert_eq!(cost(CostModel::default().with_diagonals()), Some(2004));
+        let north = CostModel::default().with_start_facing(Direction::Up);
+        assert_eq!(cost(north.clone()), Some(1005));
+        assert_eq!(cost(north.with_end_facing(Direction::Down)), Some(2005));
     }
 
     #[test]
-    fn test_test_small_txt_part_b() {
-        let puzzle = include_str!("../puzzle/test_small.txt");
-        let maze = ReindeerGraph::from_str(puzzle).unwrap();
-        assert_eq!(maze.part_b(), Some(45));
+    fn test_turnaround_is_one_route() {
+        // Turning round at the start ties with two quarter turns through the open side, but
+        // it is still only one way to go.
+        let routes = optimal_routes("#######\n#E..S.#\n####.##\n####.##\n#######\n");
+        assert_eq!((routes.cost, routes.routes), (2003, 1));
+        assert_eq!(routes.tiles.len(), 4);
     }
 
     #[test]
-    fn test_test_large_txt_part_b() {
-        let puzzle = include_str!("../puzzle/test_large.txt");
-        let maze = ReindeerGraph::from_str(puzzle).unwrap();
-        assert_eq!(maze.part_b(), Some(64));
+    fn test_zero_cost_cycle() {
+        // Turning between up and right costs nothing either way, so each is the other's
+        // predecessor and there is no counting the routes through them.
+        let (right, up) = (
+            (Point(0, 0), Direction::Right),
+            (Point(0, 0), Direction::Up),
+        );
+        let graph = ReindeerGraph {
+            graph: HashMap::from([
+                (right, vec![(up, 0)]),
+                (up, vec![(right, 0), ((Point(-1, 0), Direction::Up), 1)]),
+            ]),
+            start: Point(0, 0),
+            end: Point(-1, 0),
+            model: CostModel::default(),
+        };
+        assert_eq!(graph.part_a(), Some(1));
+        assert_eq!(graph.optimal_routes(), Err(RoutesErr::Cycle));
     }
 }
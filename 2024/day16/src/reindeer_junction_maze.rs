@@ -1,19 +1,27 @@
 use std::{collections::HashSet, str::FromStr};
 
+use tracing::trace;
 use util::{direction::Direction, point::Point};
 
-use crate::{maze_cell::MazeCell, reindeer_maze_err::ReindeerMazeErr};
+use crate::{cost_model::CostModel, maze_cell::MazeCell, reindeer_maze_err::ReindeerMazeErr};
 
 #[derive(Debug)]
 pub struct ReindeerJunctionMaze {
     grid: Vec<Vec<MazeCell>>,
     junctions: HashSet<Point>,
     reindeer_pos: Point,
+    model: CostModel,
 }
 
 impl FromStr for ReindeerJunctionMaze {
     type Err = ReindeerMazeErr;
     fn from_str(puzzle: &str) -> Result<Self, Self::Err> {
+        ReindeerJunctionMaze::new(puzzle, CostModel::default())
+    }
+}
+
+impl ReindeerJunctionMaze {
+    pub fn new(puzzle: &str, model: CostModel) -> Result<Self, ReindeerMazeErr> {
         let mut end_found = false;
         let mut reindeer_pos = None;
         let mut grid = vec![];
@@ -64,7 +72,8 @@ impl FromStr for ReindeerJunctionMaze {
                         None
                     })
                     .collect();
-                if neighbours.len() != 2 {
+                // Diagonal moves can leave a corridor anywhere, and a run has to stop at the end
+                if model.diagonals() || *cell == MazeCell::End || neighbours.len() != 2 {
                     junctions.insert(point);
                     continue;
                 }
@@ -90,48 +99,13 @@ impl FromStr for ReindeerJunctionMaze {
             grid,
             junctions,
             reindeer_pos,
+            model,
         })
     }
-}
-
-impl ReindeerJunctionMaze {
-    fn path_cost(visited: &[Point]) -> Option<usize> {
-        if visited.is_empty() {
-            return None;
-        }
-
-        let mut current_direction = Direction::Right;
-        let mut cost = 0;
-        let mut prev_point = visited.first().expect("Empty visited path");
-        for point in visited.iter().skip(1) {
-            let diff = prev_point.diff(point);
-            let dir_opt = Direction::from_point(&diff.normalize());
-            if let Some(dir) = dir_opt {
-                if dir == current_direction {
-                    cost += diff.abs();
-                } else if dir == current_direction.right90() {
-                    cost += 1000 + diff.abs();
-                    current_direction = current_direction.right90();
-                } else if dir == current_direction.left90() {
-                    cost += 1000 + diff.abs();
-                    current_direction = current_direction.left90();
-                }
-            }
-            prev_point = point;
-        }
-
-        Some(cost as usize)
-    }
-
-    fn in_bounds(&self, point: &Point) -> bool {
-        point.0 >= 0
-            && point.1 >= 1
-            && point.0 < (self.grid.len() as i32)
-            && point.1 < (self.grid[0].len() as i32)
-    }
 
     fn possible_points(&self, source: &Point, path: &[Point]) -> Vec<Point> {
-        Direction::horiz_and_vert()
+        self.model
+            .facings()
             .into_iter()
             .filter_map(|d| {
 
@@ -165,6 +139,7 @@ impl ReindeerJunctionMaze {
         let mut candidates: Vec<Vec<Point>> = vec![vec![self.reindeer_pos]];
 
         while let Some(candidate) = candidates.pop() {
+            trace!(frontier = candidates.len(), "expanding path");
             if candidate.last().is_none() {
                 continue;
             }
@@ -172,7 +147,7 @@ impl ReindeerJunctionMaze {
 
             // Check if last point is End
             if self.grid[last.0 as usize][last.1 as usize] == MazeCell::End {
-                if let Some(cost) = ReindeerJunctionMaze::path_cost(&candidate) {
+                if let Some(cost) = self.model.path_cost(&candidate) {
                     min_cost = min_cost.or(Some(usize::MAX)).map(|x| x.min(cost));
                 }
                 continue;
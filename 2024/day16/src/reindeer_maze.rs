@@ -1,18 +1,26 @@
 use std::str::FromStr;
 
-use util::{direction::Direction, point::Point};
+use tracing::trace;
+use util::point::Point;
 
-use crate::{maze_cell::MazeCell, reindeer_maze_err::ReindeerMazeErr};
+use crate::{cost_model::CostModel, maze_cell::MazeCell, reindeer_maze_err::ReindeerMazeErr};
 
 #[derive(Debug)]
 pub struct ReindeerMaze {
     grid: Vec<Vec<MazeCell>>,
     reindeer_pos: Point,
+    model: CostModel,
 }
 
 impl FromStr for ReindeerMaze {
     type Err = ReindeerMazeErr;
     fn from_str(puzzle: &str) -> Result<Self, Self::Err> {
+        ReindeerMaze::new(puzzle, CostModel::default())
+    }
+}
+
+impl ReindeerMaze {
+    pub fn new(puzzle: &str, model: CostModel) -> Result<Self, ReindeerMazeErr> {
         let mut end_found = false;
         let mut reindeer_pos = None;
         let mut grid = vec![];
@@ -44,39 +52,16 @@ impl FromStr for ReindeerMaze {
             return Err(ReindeerMazeErr::NoEndFound);
         }
 
-        Ok(Self { grid, reindeer_pos })
-    }
-}
-
-impl ReindeerMaze {
-    fn path_cost(visited: &Vec<Point>) -> Option<usize> {
-        if visited.is_empty() {
-            return None;
-        }
-
-        let mut current_direction = Direction::Right;
-        let mut cost = 0;
-        let mut prev_point = visited.first().expect("Empty visited path");
-        for point in visited.iter().skip(1) {
-            if let Some(dir) = prev_point.direction_of(point) {
-                if dir == current_direction {
-                    cost += 1;
-                } else if dir == current_direction.right90() {
-                    cost += 1001;
-                    current_direction = current_direction.right90();
-                } else if dir == current_direction.left90() {
-                    cost += 1001;
-                    current_direction = current_direction.left90();
-                }
-            }
-            prev_point = point;
-        }
-
-        Some(cost)
+        Ok(Self {
+            grid,
+            reindeer_pos,
+            model,
+        })
     }
 
-    fn possible_points(&self, source: &Point, path: &Vec<Point>) -> Vec<Point> {
-        Direction::horiz_and_vert()
+    fn possible_points(&self, source: &Point, path: &[Point]) -> Vec<Point> {
+        self.model
+            .facings()
             .into_iter()
             .filter_map(|d| {
                 let next_point = source.add(&d.to_point());
@@ -97,6 +82,7 @@ impl ReindeerMaze {
         let mut candidates: Vec<Vec<Point>> = vec![vec![self.reindeer_pos]];
 
         while let Some(candidate) = candidates.pop() {
+            trace!(frontier = candidates.len(), "expanding path");
             if candidate.last().is_none() {
                 continue;
             }
@@ -104,7 +90,7 @@ impl ReindeerMaze {
 
             // Check if last point is End
             if self.grid[last.0 as usize][last.1 as usize] == MazeCell::End {
-                if let Some(cost) = ReindeerMaze::path_cost(&candidate) {
+                if let Some(cost) = self.model.path_cost(&candidate) {
                     min_cost = min_cost
                         .or(Some(usize::MAX))
                         .map(|x| x.min(cost));
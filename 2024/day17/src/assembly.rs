@@ -0,0 +1,306 @@
+use std::{fmt, str::FromStr};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssemblyErr {
+    UnknownOpcode(u64),
+    MissingOperand(usize),
+    UnknownMnemonic(usize, String),
+    BadOperand(usize, String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Opcode {
+    Adv,
+    Bxl,
+    Bst,
+    Jnz,
+    Bxc,
+    Out,
+    Bdv,
+    Cdv,
+}
+
+impl Opcode {
+    pub const ALL: [Opcode; 8] = [
+        Opcode::Adv,
+        Opcode::Bxl,
+        Opcode::Bst,
+        Opcode::Jnz,
+        Opcode::Bxc,
+        Opcode::Out,
+        Opcode::Bdv,
+        Opcode::Cdv,
+    ];
+
+    pub fn from_u64(opcode: u64) -> Option<Opcode> {
+        Opcode::ALL.get(opcode as usize).copied()
+    }
+
+    pub fn to_u64(self) -> u64 {
+        self as u64
+    }
+
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Opcode::Adv => "adv",
+            Opcode::Bxl => "bxl",
+            Opcode::Bst => "bst",
+            Opcode::Jnz => "jnz",
+            Opcode::Bxc => "bxc",
+            Opcode::Out => "out",
+            Opcode::Bdv => "bdv",
+            Opcode::Cdv => "cdv",
+        }
+    }
+
+    /// Whether the operand is a combo operand, which can name a register, rather than a literal.
+    pub fn takes_combo(self) -> bool {
+        matches!(
+            self,
+            Opcode::Adv | Opcode::Bst | Opcode::Out | Opcode::Bdv | Opcode::Cdv
+        )
+    }
+}
+
+/// A decoded operand. Combo operands 4 to 6 read a register and 7 is reserved; literal operands
+/// are always just their value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Literal(u64),
+    A,
+    B,
+    C,
+    Reserved,
+}
+
+impl Operand {
+    pub fn combo(operand: u64) -> Operand {
+        match operand {
+            4 => Operand::A,
+            5 => Operand::B,
+            6 => Operand::C,
+            7 => Operand::Reserved,
+            n => Operand::Literal(n),
+        }
+    }
+
+    fn to_u64(self) -> u64 {
+        match self {
+            Operand::Literal(n) => n,
+            Operand::A => 4,
+            Operand::B => 5,
+            Operand::C => 6,
+            Operand::Reserved => 7,
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Literal(n) => write!(f, "{}", n),
+            Operand::A => write!(f, "A"),
+            Operand::B => write!(f, "B"),
+            Operand::C => write!(f, "C"),
+            Operand::Reserved => write!(f, "reserved"),
+        }
+    }
+}
+
+impl FromStr for Operand {
+    type Err = ();
+    fn from_str(operand: &str) -> Result<Self, Self::Err> {
+        match operand {
+            "A" | "a" => Ok(Operand::A),
+            "B" | "b" => Ok(Operand::B),
+            "C" | "c" => Ok(Operand::C),
+            "reserved" => Ok(Operand::Reserved),
+            _ => operand.parse().map(Operand::Literal).map_err(|_| ()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    pub operand: Operand,
+}
+
+impl Instruction {
+    pub fn decode(opcode: u64, operand: u64) -> Result<Instruction, AssemblyErr> {
+        let opcode = Opcode::from_u64(opcode).ok_or(AssemblyErr::UnknownOpcode(opcode))?;
+        let operand = match opcode.takes_combo() {
+            true => Operand::combo(operand),
+            false => Operand::Literal(operand),
+        };
+        Ok(Instruction { opcode, operand })
+    }
+
+    pub fn encode(&self) -> [u64; 2] {
+        [self.opcode.to_u64(), self.operand.to_u64()]
+    }
+
+    /// What the instruction does, as a comment for listings.
+    pub fn describe(&self) -> String {
+        let x = self.operand;
+        match self.opcode {
+            Opcode::Adv => format!("A = A >> {}", x),
+            Opcode::Bxl => format!("B = B ^ {}", x),
+            Opcode::Bst => format!("B = {} % 8", x),
+            Opcode::Jnz => format!("if A != 0 goto {}", x),
+            Opcode::Bxc => "B = B ^ C".to_string(),
+            Opcode::Out => format!("output {} % 8", x),
+            Opcode::Bdv => format!("B = A >> {}", x),
+            Opcode::Cdv => format!("C = A >> {}", x),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.opcode.mnemonic(), self.operand)
+    }
+}
+
+/// Decodes a program into instructions, two numbers at a time.
+pub fn disassemble(program: &[u64]) -> Result<Vec<Instruction>, AssemblyErr> {
+    program
+        .chunks(2)
+        .enumerate()
+        .map(|(idx, pair)| match pair {
+            [opcode, operand] => Instruction::decode(*opcode, *operand),
+            _ => Err(AssemblyErr::MissingOperand(idx * 2)),
+        })
+        .collect()
+}
+
+/// One line per instruction with its address and what it does, which `assemble` reads back.
+pub fn listing(program: &[u64]) -> Result<String, AssemblyErr> {
+    let lines = disassemble(program)?
+        .iter()
+        .enumerate()
+        .map(|(idx, instruction)| {
+            let text = format!("{:>2}: {}", idx * 2, instruction);
+            format!("{:<14}; {}\n", text, instruction.describe())
+        })
+        .collect();
+    Ok(lines)
+}
+
+/// Turns assembly back into a program. Each line is a mnemonic and an operand, optionally after
+/// an `N:` address and before a `;` comment. Combo operands may be `A`, `B`, `C` or a number.
+pub fn assemble(text: &str) -> Result<Vec<u64>, AssemblyErr> {
+    let mut program = vec![];
+    for (idx, line) in text.lines().enumerate() {
+        let line_no = idx + 1;
+        let line = line.split(';').next().unwrap_or_default();
+        let line = line.split_once(':').map_or(line, |(_, rest)| rest).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut words = line.split_whitespace();
+        let mnemonic = words.next().unwrap_or_default();
+        let opcode = Opcode::ALL
+            .into_iter()
+            .find(|op| op.mnemonic() == mnemonic)
+            .ok_or_else(|| AssemblyErr::UnknownMnemonic(line_no, mnemonic.to_string()))?;
+        let bad_operand = || AssemblyErr::BadOperand(line_no, line.to_string());
+        let operand = match (words.next(), words.next()) {
+            (None, _) if opcode == Opcode::Bxc => Operand::Literal(0),
+            (Some(operand), None) => operand.parse().map_err(|_| bad_operand())?,
+            _ => return Err(bad_operand()),
+        };
+        let valid = match operand {
+            Operand::Literal(n) if opcode.takes_combo() => n <= 3,
+            Operand::Literal(n) => n <= 7,
+            _ => opcode.takes_combo(),
+        };
+        if !valid {
+            return Err(bad_operand());
+        }
+        program.extend(Instruction { opcode, operand }.encode());
+    }
+    Ok(program)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::computer::Computer;
+    use util::input;
+
+    const EXAMPLE: [u64; 6] = [0, 1, 5, 4, 3, 0];
+
+    #[test]
+    fn test_disassemble() {
+        let instructions = disassemble(&EXAMPLE).unwrap();
+        let text: Vec<_> = instructions.iter().map(|i| i.to_string()).collect();
+        assert_eq!(text, vec!["adv 1", "out A", "jnz 0"]);
+        assert_eq!(
+            listing(&EXAMPLE).unwrap(),
+            concat!(
+                " 0: adv 1     ; A = A >> 1\n",
+                " 2: out A     ; output A % 8\n",
+                " 4: jnz 0     ; if A != 0 goto 0\n",
+            )
+        );
+        assert_eq!(disassemble(&[0, 1, 5]), Err(AssemblyErr::MissingOperand(2)));
+        assert_eq!(disassemble(&[8, 1]), Err(AssemblyErr::UnknownOpcode(8)));
+        assert_eq!(disassemble(&[2, 7]).unwrap()[0].to_string(), "bst reserved");
+    }
+
+    #[test]
+    fn test_assemble() {
+        let text = "
+            ; divide A by 8 until it is zero, printing each step
+            adv 3
+            out a
+            bxc
+            jnz 0
+        ";
+        assert_eq!(assemble(text), Ok(vec![0, 3, 5, 4, 4, 0, 3, 0]));
+        assert_eq!(
+            assemble("mul 3"),
+            Err(AssemblyErr::UnknownMnemonic(1, "mul".to_string()))
+        );
+        assert_eq!(
+            assemble("adv 5"),
+            Err(AssemblyErr::BadOperand(1, "adv 5".to_string()))
+        );
+        assert_eq!(
+            assemble("\nbxl A"),
+            Err(AssemblyErr::BadOperand(2, "bxl A".to_string()))
+        );
+        assert_eq!(
+            assemble("jnz"),
+            Err(AssemblyErr::BadOperand(1, "jnz".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let programs = [
+            EXAMPLE.to_vec(),
+            vec![0, 3, 5, 4, 3, 0],
+            vec![2, 4, 1, 1, 7, 5, 4, 7, 1, 4, 0, 3, 5, 5, 3, 0],
+            vec![2, 7, 4, 6],
+        ];
+        for program in programs {
+            assert_eq!(assemble(&listing(&program).unwrap()), Ok(program));
+        }
+    }
+
+    #[test]
+    fn test_round_trip_puzzles() {
+        let mut puzzles = vec![
+            include_str!("../puzzle/test.txt").to_string(),
+            include_str!("../puzzle/test_own_output.txt").to_string(),
+        ];
+        puzzles.extend(input::real_input(env!("CARGO_MANIFEST_DIR")));
+        for puzzle in puzzles {
+            let program = Computer::from_str(&puzzle).unwrap().program().to_vec();
+            assert_eq!(assemble(&listing(&program).unwrap()), Ok(program));
+        }
+    }
+}
@@ -1,5 +1,18 @@
 use std::str::FromStr;
 
+use crate::{
+    assembly::{self, assemble, Instruction, Opcode, Operand},
+    computer_error::{ComputerError, QuineError},
+    debugger::Debugger,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Register {
+    A,
+    B,
+    C,
+}
+
 #[derive(Debug)]
 pub struct Computer {
     a: u64,
@@ -14,201 +27,340 @@ impl Clone for Computer {
             a: self.a,
             b: self.b,
             c: self.c,
-            program: self.program.to_vec()
+            program: self.program.to_vec(),
         }
     }
 }
 
+fn parse_register(line: Option<&str>, line_no: usize, name: char) -> Result<u64, ComputerError> {
+    line.and_then(|line| line.strip_prefix(&format!("Register {}: ", name)))
+        .and_then(|value| value.parse().ok())
+        .ok_or(ComputerError::BadRegisterLine(line_no))
+}
+
+fn parse_program(line: &str) -> Result<Vec<u64>, ComputerError> {
+    let digits = line
+        .strip_prefix("Program: ")
+        .ok_or(ComputerError::MissingProgram)?;
+    let program = digits
+        .split(',')
+        .map(|digit| {
+            digit
+                .parse()
+                .map_err(|_| ComputerError::BadProgramDigit(digit.to_string()))
+        })
+        .collect::<Result<Vec<u64>, _>>()?;
+    if let Some((idx, &value)) = program.iter().enumerate().find(|(_, &value)| value > 7) {
+        return Err(match idx % 2 {
+            0 => ComputerError::BadOpcode(idx, value),
+            _ => ComputerError::BadOperand(idx, value),
+        });
+    }
+    if program.len() % 2 != 0 {
+        return Err(ComputerError::OddProgramLength(program.len()));
+    }
+    Ok(program)
+}
+
 impl FromStr for Computer {
-    type Err = anyhow::Error;
+    type Err = ComputerError;
+    /// Three registers, a blank line, then either a `Program:` line or an assembly listing.
     fn from_str(puzzle: &str) -> Result<Self, Self::Err> {
-        let lines: Vec<&str> = puzzle.lines().collect();
-        if lines.len() != 5 {
-            return Err(anyhow::Error::msg("Invalid length"));
+        let mut lines = puzzle.lines();
+        let a = parse_register(lines.next(), 1, 'A')?;
+        let b = parse_register(lines.next(), 2, 'B')?;
+        let c = parse_register(lines.next(), 3, 'C')?;
+        if lines.next().is_some_and(|line| !line.trim().is_empty()) {
+            return Err(ComputerError::UnexpectedLine(4));
         }
 
-        let a = lines[0]
-            .split_whitespace()
-            .map(|x| x.parse::<u64>())
-            .find(&Result::is_ok)
-            .unwrap_or(Ok(0))?;
-        let b = lines[1]
-            .split_whitespace()
-            .map(|x| x.parse::<u64>())
-            .find(&Result::is_ok)
-            .unwrap_or(Ok(0))?;
-        let c = lines[2]
-            .split_whitespace()
-            .map(|x| x.parse::<u64>())
-            .find(&Result::is_ok)
-            .unwrap_or(Ok(0))?;
-
-        let program = lines[4]
-            .split_whitespace()
-            .nth(1)
-            .ok_or(anyhow::Error::msg("No program found"))?
-            .split(",")
-            .filter_map(|n| n.parse().ok())
-            .collect();
+        let rest: Vec<&str> = lines.collect();
+        let program = match rest.first() {
+            None => return Err(ComputerError::MissingProgram),
+            Some(line) if line.starts_with("Program:") => {
+                let extra = rest.iter().skip(1).position(|line| !line.trim().is_empty());
+                if let Some(idx) = extra {
+                    return Err(ComputerError::UnexpectedLine(idx + 6));
+                }
+                parse_program(line)?
+            }
+            Some(_) => assemble(&rest.join("\n")).map_err(ComputerError::BadAssembly)?,
+        };
+        if program.is_empty() {
+            return Err(ComputerError::MissingProgram);
+        }
 
         Ok(Computer { a, b, c, program })
     }
 }
 
 impl Computer {
-    fn get_operand(&self, operand: u64) -> Option<u64> {
+    pub fn program(&self) -> &[u64] {
+        &self.program
+    }
+
+    pub fn register(&self, register: Register) -> u64 {
+        match register {
+            Register::A => self.a,
+            Register::B => self.b,
+            Register::C => self.c,
+        }
+    }
+
+    /// The instruction starting at `pc`, which must leave room for its operand.
+    pub fn instruction_at(&self, pc: usize) -> Result<Instruction, ComputerError> {
+        match (self.program.get(pc), self.program.get(pc + 1)) {
+            (Some(&opcode), Some(&operand)) => Instruction::decode(opcode, operand)
+                .map_err(|_| ComputerError::BadOpcode(pc, opcode)),
+            _ => Err(ComputerError::PcOutOfRange(pc)),
+        }
+    }
+
+    fn value(&self, operand: Operand, pc: usize) -> Result<u64, ComputerError> {
         match operand {
-            0..=3 => Some(operand),
-            4 => Some(self.a),
-            5 => Some(self.b),
-            6 => Some(self.c),
-            // 7 is reserved
-            _ => None,
+            Operand::Literal(n) => Ok(n),
+            Operand::A => Ok(self.a),
+            Operand::B => Ok(self.b),
+            Operand::C => Ok(self.c),
+            Operand::Reserved => Err(ComputerError::ReservedOperand(pc)),
         }
     }
 
-    fn tick(&mut self, pc: usize) -> (usize, Option<u64>) {
-        let opcode = self.program[pc];
+    /// A divided by 2 to the power of `by`, which is zero once every bit has been shifted out.
+    fn shifted(&self, by: u64) -> u64 {
+        u32::try_from(by)
+            .ok()
+            .and_then(|by| self.a.checked_shr(by))
+            .unwrap_or(0)
+    }
+
+    /// Executes the instruction at `pc`, returning the next `pc` and anything output.
+    pub fn tick(&mut self, pc: usize) -> Result<(usize, Option<u64>), ComputerError> {
+        let Instruction { opcode, operand } = self.instruction_at(pc)?;
+        let value = self.value(operand, pc)?;
         match opcode {
-            0 => {
-                self.a >>= self.get_operand(self.program[pc + 1]).unwrap();
-            }
-            1 => {
-                self.b ^= self.program[pc + 1];
-            }
-            2 => {
-                let combo = self.get_operand(self.program[pc + 1]).unwrap();
-                self.b = combo & 0b111;
-            }
-            3 => {
-                if self.a != 0 {
-                    return (self.program[pc + 1] as usize, None);
-                }
-            }
-            4 => {
-                self.b ^= self.c;
-            }
-            5 => {
-                let operand = self.get_operand(self.program[pc + 1]).unwrap();
-                return (pc + 2, Some(operand & 0x7));
-            }
-            6 => {
-                self.b = self.a >> self.get_operand(self.program[pc + 1]).unwrap();
-            }
-            7 => {
-                self.c = self.a >> self.get_operand(self.program[pc + 1]).unwrap();
-            }
-            _ => (),
+            Opcode::Adv => self.a = self.shifted(value),
+            Opcode::Bxl => self.b ^= value,
+            Opcode::Bst => self.b = value & 0b111,
+            Opcode::Jnz if self.a != 0 => return Ok((value as usize, None)),
+            Opcode::Jnz => (),
+            Opcode::Bxc => self.b ^= self.c,
+            Opcode::Out => return Ok((pc + 2, Some(value & 0b111))),
+            Opcode::Bdv => self.b = self.shifted(value),
+            Opcode::Cdv => self.c = self.shifted(value),
         }
-        (pc + 2, None)
-    }
-
-    fn produces_own_program(&mut self) -> bool {
-        let mut outputs: Vec<_> = self.program.iter().copied().rev().collect();
-        let mut pc = 0;
-        while !outputs.is_empty() {
-            let (new_pc, out_opt) = self.tick(pc);
-            pc = new_pc;
-            if let Some(out) = out_opt {
-                if let Some(required) = outputs.pop() {
-                    if out != required {
-                        return false;
-                    }
-                }
-            }
-            if pc >= self.program.len() {
-                return false;
-            }
-        }
-        true
+        Ok((pc + 2, None))
     }
 
-    fn solve_test_txt(&self) -> u64 {
-        let mut acc = 0;
-        for digit in self.program.iter().rev() {
-            acc = ((acc & !0b111) | digit) << 3;
+    /// Checks the shape that makes the quine search work: one pass of a loop consumes the low
+    /// three bits of A and outputs once, so each output depends only on what is left of A.
+    fn check_quine_pattern(&self) -> Result<(), QuineError> {
+        let instructions = assembly::disassemble(&self.program).map_err(QuineError::Undecodable)?;
+        let count = |opcode: Opcode| instructions.iter().filter(|i| i.opcode == opcode).count();
+        let last = instructions.last().map(|i| (i.opcode, i.operand));
+        if count(Opcode::Jnz) != 1 || last != Some((Opcode::Jnz, Operand::Literal(0))) {
+            return Err(QuineError::NoLoop);
         }
-        acc
-    }
-
-    pub fn solve_input_txt(&self) -> Option<u64> {
-        let mut acc_candidates = vec![0];
-        for digit in self.program.iter().rev() {
-            let mut current_cands = vec![];
-            while let Some(acc) = acc_candidates.pop() {
-                for b_cand in 0..8 {
-                    let mut b = b_cand ^ 1;
-                    let c = acc >> b;
-                    b ^= c;
-                    b ^= 0b100;
-                    b &= 0b111;
-                    if b == *digit {
-                        let next_acc = ((acc & !0b111) | b_cand) << 3;
-                        current_cands.push(next_acc)
-                    }
-                }
-            }
-            acc_candidates = current_cands.to_vec();
+        let shifts: Vec<_> = instructions
+            .iter()
+            .filter(|i| i.opcode == Opcode::Adv)
+            .collect();
+        if shifts.len() != 1 || shifts[0].operand != Operand::Literal(3) {
+            return Err(QuineError::NoShiftByThree);
         }
+        match count(Opcode::Out) {
+            1 => Ok(()),
+            outputs => Err(QuineError::OutputsPerPass(outputs)),
+        }
+    }
 
-        let final_candidates: Vec<u64> = acc_candidates.iter().map(|x| x >> 3).collect();
+    fn outputs_for(&self, a: u64) -> Result<Vec<u64>, ComputerError> {
+        let mut computer = self.clone();
+        computer.a = a;
+        let mut debugger = Debugger::new(computer);
+        debugger.run()?;
+        Ok(debugger.outputs().to_vec())
+    }
 
-        // Go through our acc candidates to find the one that works
-        for cand in final_candidates.iter() {
-            let mut comp = self.clone();
-            comp.a = *cand;
-            if comp.produces_own_program() {
-                return Some(*cand);
+    /// The smallest A, among those ending in the three bits `a` once shifted up, that makes the
+    /// program output the last `matched` digits of itself and then the rest.
+    fn search_quine(&self, a: u64, matched: usize) -> Result<Option<u64>, ComputerError> {
+        if matched == self.program.len() {
+            return Ok(Some(a));
+        }
+        let wanted = &self.program[self.program.len() - matched - 1..];
+        for bits in 0..8 {
+            let candidate = (a << 3) | bits;
+            if self.outputs_for(candidate)? != wanted {
+                continue;
+            }
+            if let Some(found) = self.search_quine(candidate, matched + 1)? {
+                return Ok(Some(found));
             }
         }
-        None
+        Ok(None)
     }
 
-    fn get_outputs(&mut self) -> String {
-        let mut pc = 0;
-        let mut outputs: Vec<u64> = vec![];
-        while pc < self.program.len() {
-            let (new_pc, out_opt) = self.tick(pc);
-            pc = new_pc;
-            if let Some(out) = out_opt {
-                outputs.push(out);
-            }
-        }
-        outputs
-            .iter()
-            .map(|n| n.to_string())
-            .collect::<Vec<String>>()
-            .join(",")
+    /// Builds A three bits at a time from the most significant end, running the real program
+    /// to check each candidate produces the matching tail of itself.
+    pub fn find_quine(&self) -> Result<u64, QuineError> {
+        self.check_quine_pattern()?;
+        self.search_quine(0, 0)
+            .map_err(QuineError::Computer)?
+            .ok_or(QuineError::NotFound)
+    }
+
+    fn get_outputs(&self) -> Result<String, ComputerError> {
+        let mut debugger = Debugger::new(self.clone());
+        debugger.run()?;
+        let outputs: Vec<String> = debugger.outputs().iter().map(|n| n.to_string()).collect();
+        Ok(outputs.join(","))
     }
 
-    pub fn part_a(&mut self) -> String {
+    pub fn part_a(&self) -> Result<String, ComputerError> {
         self.get_outputs()
     }
 
-    pub fn part_b(&mut self) -> u64 {
-        let orig_bc = (self.b, self.c);
-        (0..).find(|a_cand| {
-            self.a = *a_cand;
-            (self.b, self.c) = orig_bc;
-            self.produces_own_program()
-        }).unwrap()
+    pub fn part_b(&self) -> Result<u64, QuineError> {
+        self.find_quine()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::assembly::AssemblyErr;
+    use util::{
+        example::{self, ExampleErr},
+        input,
+        output::Answer,
+    };
+    fn with_program(listing: &str) -> Computer {
+        let puzzle = format!("Register A: 0\nRegister B: 0\nRegister C: 0\n\n{}", listing);
+        Computer::from_str(&puzzle).unwrap()
+    }
+
+    const REGISTERS: &str = "Register A: 1\nRegister B: 2\nRegister C: 3\n";
+
     #[test]
-    fn test_test_txt() {
-        let expected = "4,6,3,5,6,3,5,2,1,0";
-        let puzzle = include_str!("../puzzle/test.txt");
-        let mut computer = Computer::from_str(puzzle).unwrap();
-        assert_eq!(expected, computer.part_a());
+    fn test_parse_errors() {
+        let cases = [
+            (
+                "Register A: 1\nRegister B: x\n",
+                ComputerError::BadRegisterLine(2),
+            ),
+            (
+                "Register A: 1\nRegister C: 0\n",
+                ComputerError::BadRegisterLine(2),
+            ),
+            (
+                "Register A: 1\nRegister B: 0",
+                ComputerError::BadRegisterLine(3),
+            ),
+            ("Program: 0,1", ComputerError::UnexpectedLine(4)),
+            ("", ComputerError::MissingProgram),
+            ("\nProgram: 0,1\n\n5,5", ComputerError::UnexpectedLine(7)),
+            (
+                "\nProgram: 0,1,,5",
+                ComputerError::BadProgramDigit("".to_string()),
+            ),
+            ("\nProgram: 0,1,8,5", ComputerError::BadOpcode(2, 8)),
+            ("\nProgram: 0,9", ComputerError::BadOperand(1, 9)),
+            ("\nProgram: 0,1,5", ComputerError::OddProgramLength(3)),
+            (
+                "\nadv 9",
+                ComputerError::BadAssembly(AssemblyErr::BadOperand(1, "adv 9".to_string())),
+            ),
+        ];
+        for (puzzle, err) in cases {
+            let puzzle = match puzzle.starts_with("Register") {
+                true => puzzle.to_string(),
+                false => format!("{}{}", REGISTERS, puzzle),
+            };
+            assert_eq!(Computer::from_str(&puzzle).unwrap_err(), err, "{}", puzzle);
+        }
+
+        let computer = Computer::from_str(&format!("{}\nProgram: 0,1\n\n", REGISTERS)).unwrap();
+        assert_eq!((computer.a, computer.b, computer.c), (1, 2, 3));
+        assert_eq!(computer.program, vec![0, 1]);
     }
+
     #[test]
-    fn test_test_own_output_txt() {
-        let puzzle = include_str!("../puzzle/test_own_output.txt");
-        let computer = Computer::from_str(puzzle).unwrap();
-        assert_eq!(117440, computer.solve_test_txt());
+    fn test_run_errors() {
+        let computer = Computer {
+            a: 1,
+            ..with_program("bst A\nout reserved")
+        };
+        assert_eq!(computer.part_a(), Err(ComputerError::ReservedOperand(2)));
+        let computer = Computer {
+            a: 1,
+            b: 0,
+            c: 0,
+            program: vec![3, 1, 5, 4],
+        };
+        assert_eq!(computer.part_a(), Err(ComputerError::PcOutOfRange(3)));
+        let computer = Computer {
+            a: u64::MAX,
+            b: 100,
+            ..with_program("adv B\nout A")
+        };
+        assert_eq!(computer.part_a(), Ok("0".to_string()));
+    }
+
+    #[test]
+    fn test_quine_pattern() {
+        let cases = [
+            ("adv 3\nout A", QuineError::NoLoop),
+            ("adv 3\nout A\njnz 2\njnz 0", QuineError::NoLoop),
+            ("bst A\nout B\njnz 0", QuineError::NoShiftByThree),
+            ("adv A\nout B\njnz 0", QuineError::NoShiftByThree),
+            ("adv 3\nout A\nout B\njnz 0", QuineError::OutputsPerPass(2)),
+            ("adv 3\nout B\njnz 0", QuineError::NotFound),
+        ];
+        for (listing, err) in cases {
+            assert_eq!(with_program(listing).find_quine(), Err(err), "{}", listing);
+        }
+    }
+
+    #[test]
+    fn test_find_quine() {
+        let computer = with_program("bst A\nbxl 5\ncdv B\nbxl 6\nadv 3\nbxc\nout B\njnz 0");
+        let a = computer.find_quine().unwrap();
+        assert_eq!(computer.outputs_for(a).unwrap(), computer.program);
+        for smaller in 0..a.min(1 << 12) {
+            assert_ne!(computer.outputs_for(smaller).unwrap(), computer.program);
+        }
+    }
+
+    #[test]
+    fn test_find_quine_real_input() {
+        let Some(puzzle) = input::real_input(env!("CARGO_MANIFEST_DIR")) else {
+            return;
+        };
+        let computer = Computer::from_str(&puzzle).unwrap();
+        let a = computer.find_quine().unwrap();
+        assert_eq!(computer.outputs_for(a).unwrap(), computer.program);
+    }
+
+    #[test]
+    fn test_assembled_program() {
+        let computer = with_program("0: adv 3\nout A ; print\njnz 0\n");
+        let computer = Computer {
+            a: 2024,
+            ..computer
+        };
+        assert_eq!(Ok("5,7,3,0".to_string()), computer.part_a());
+    }
+
+    #[test]
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            let computer = Computer::from_str(&example.puzzle).unwrap();
+            match part {
+                "A" => Ok(computer.part_a().render()),
+                "B" => Ok(computer.part_b().render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
     }
 }
@@ -0,0 +1,31 @@
+use crate::assembly::AssemblyErr;
+
+/// Problems with a program, found either reading it or running it. Lines are numbered from 1
+/// and addresses from 0.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ComputerError {
+    BadRegisterLine(usize),
+    UnexpectedLine(usize),
+    MissingProgram,
+    BadProgramDigit(String),
+    BadAssembly(AssemblyErr),
+    BadOpcode(usize, u64),
+    BadOperand(usize, u64),
+    OddProgramLength(usize),
+    ReservedOperand(usize),
+    PcOutOfRange(usize),
+    CycleLimit(usize),
+}
+
+/// Why no register A could be found that makes a program output itself.
+#[derive(Debug, PartialEq)]
+pub enum QuineError {
+    Undecodable(AssemblyErr),
+    /// The program is not a single loop ending in `jnz 0`.
+    NoLoop,
+    /// A is not shifted right by exactly three bits once per pass.
+    NoShiftByThree,
+    OutputsPerPass(usize),
+    NotFound,
+    Computer(ComputerError),
+}
@@ -0,0 +1,389 @@
+use std::fmt;
+
+use util::explorer::{Frame, Simulation};
+
+use crate::{
+    assembly::{self, Instruction},
+    computer::{Computer, Register},
+    computer_error::ComputerError,
+};
+
+/// How many instructions a `Debugger` runs before giving up, unless given another limit.
+pub const CYCLE_LIMIT: usize = 100_000;
+
+const REGISTERS: [Register; 3] = [Register::A, Register::B, Register::C];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Stops before the instruction at this address runs.
+    Pc(usize),
+    /// Stops after any instruction that outputs.
+    Output,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Watch {
+    /// Stops when the register becomes this value, not while it stays there.
+    Equals(Register, u64),
+}
+
+impl Watch {
+    fn triggered(&self, before: &[u64; 3], after: &[u64; 3]) -> bool {
+        match self {
+            Watch::Equals(register, value) => {
+                let idx = *register as usize;
+                before[idx] != *value && after[idx] == *value
+            }
+        }
+    }
+}
+
+/// Why a run stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    Halted,
+    Breakpoint(Breakpoint),
+    Watch(Watch),
+}
+
+/// One executed instruction, with the registers as it left them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEntry {
+    pub cycle: usize,
+    pub pc: usize,
+    pub instruction: Instruction,
+    pub registers: [u64; 3],
+    pub output: Option<u64>,
+}
+
+impl fmt::Display for TraceEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c] = self.registers;
+        write!(
+            f,
+            "{:>5} {:>2}: {:<12} A={} B={} C={}",
+            self.cycle,
+            self.pc,
+            self.instruction.to_string(),
+            a,
+            b,
+            c
+        )?;
+        if let Some(output) = self.output {
+            write!(f, " out={}", output)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs a `Computer` an instruction at a time, stopping at breakpoints and watched registers,
+/// and gives up after its cycle limit so a program that never halts cannot hang its caller.
+#[derive(Debug, Clone)]
+pub struct Debugger {
+    computer: Computer,
+    pc: usize,
+    cycles: usize,
+    cycle_limit: usize,
+    breakpoints: Vec<Breakpoint>,
+    watches: Vec<Watch>,
+    outputs: Vec<u64>,
+    trace: Option<Vec<TraceEntry>>,
+    last_stop: Option<Stop>,
+    error: Option<ComputerError>,
+}
+
+impl Debugger {
+    pub fn new(computer: Computer) -> Debugger {
+        Debugger {
+            computer,
+            pc: 0,
+            cycles: 0,
+            cycle_limit: CYCLE_LIMIT,
+            breakpoints: vec![],
+            watches: vec![],
+            outputs: vec![],
+            trace: None,
+            last_stop: None,
+            error: None,
+        }
+    }
+
+    pub fn with_breakpoint(mut self, breakpoint: Breakpoint) -> Debugger {
+        self.breakpoints.push(breakpoint);
+        self
+    }
+
+    pub fn with_watch(mut self, watch: Watch) -> Debugger {
+        self.watches.push(watch);
+        self
+    }
+
+    pub fn with_cycle_limit(mut self, limit: usize) -> Debugger {
+        self.cycle_limit = limit;
+        self
+    }
+
+    /// Keeps a log of every instruction executed from now on.
+    pub fn with_trace(mut self) -> Debugger {
+        self.trace = Some(vec![]);
+        self
+    }
+
+    pub fn computer(&self) -> &Computer {
+        &self.computer
+    }
+
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    pub fn cycles(&self) -> usize {
+        self.cycles
+    }
+
+    pub fn outputs(&self) -> &[u64] {
+        &self.outputs
+    }
+
+    pub fn trace(&self) -> &[TraceEntry] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    /// What the most recent step stopped on, if anything.
+    pub fn last_stop(&self) -> Option<Stop> {
+        self.last_stop
+    }
+
+    /// The error the most recent step failed with, if it did.
+    pub fn error(&self) -> Option<&ComputerError> {
+        self.error.as_ref()
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.pc >= self.computer.program().len()
+    }
+
+    fn registers(&self) -> [u64; 3] {
+        REGISTERS.map(|register| self.computer.register(register))
+    }
+
+    /// Executes one instruction, returning the breakpoint or watch it triggered, or `Halted`
+    /// once the program has finished.
+    pub fn step(&mut self) -> Result<Option<Stop>, ComputerError> {
+        let result = self.execute();
+        self.error = result.as_ref().err().cloned();
+        result
+    }
+
+    fn execute(&mut self) -> Result<Option<Stop>, ComputerError> {
+        if self.is_halted() {
+            self.last_stop = Some(Stop::Halted);
+            return Ok(self.last_stop);
+        }
+        if self.cycles >= self.cycle_limit {
+            return Err(ComputerError::CycleLimit(self.cycle_limit));
+        }
+
+        let pc = self.pc;
+        let before = self.registers();
+        let instruction = self.computer.instruction_at(pc)?;
+        let (next_pc, output) = self.computer.tick(pc)?;
+        let after = self.registers();
+        self.pc = next_pc;
+        self.cycles += 1;
+        self.outputs.extend(output);
+        if let Some(trace) = self.trace.as_mut() {
+            trace.push(TraceEntry {
+                cycle: self.cycles,
+                pc,
+                instruction,
+                registers: after,
+                output,
+            });
+        }
+
+        let breakpoint = self.breakpoints.iter().find(|breakpoint| match breakpoint {
+            Breakpoint::Pc(address) => *address == next_pc,
+            Breakpoint::Output => output.is_some(),
+        });
+        let watch = self
+            .watches
+            .iter()
+            .find(|watch| watch.triggered(&before, &after));
+        self.last_stop = match (breakpoint, watch) {
+            (Some(breakpoint), _) => Some(Stop::Breakpoint(*breakpoint)),
+            (None, Some(watch)) => Some(Stop::Watch(*watch)),
+            (None, None) if self.is_halted() => Some(Stop::Halted),
+            (None, None) => None,
+        };
+        Ok(self.last_stop)
+    }
+
+    /// Steps until a breakpoint, a watch or the end of the program.
+    pub fn run(&mut self) -> Result<Stop, ComputerError> {
+        loop {
+            if let Some(stop) = self.step()? {
+                return Ok(stop);
+            }
+        }
+    }
+}
+
+impl Simulation for Debugger {
+    /// A step that fails still counts, so the error shows in its frame, but it is the last.
+    fn step(&mut self) -> bool {
+        if self.is_halted() || self.error.is_some() {
+            return false;
+        }
+        _ = Debugger::step(self);
+        true
+    }
+
+    /// The registers, the listing with the current instruction marked, and the last few
+    /// instructions executed.
+    fn frame(&self) -> Frame {
+        let [a, b, c] = self.registers();
+        let mut lines = vec![
+            format!("A: {}", a),
+            format!("B: {}", b),
+            format!("C: {}", c),
+            format!("pc: {}  cycle: {}", self.pc(), self.cycles()),
+            String::new(),
+        ];
+        let listing = assembly::listing(self.computer.program()).unwrap_or_default();
+        for (idx, line) in listing.lines().enumerate() {
+            let marker = if idx * 2 == self.pc() { '>' } else { ' ' };
+            lines.push(format!("{} {}", marker, line));
+        }
+        let outputs: Vec<String> = self.outputs.iter().map(|n| n.to_string()).collect();
+        lines.push(String::new());
+        lines.push(format!("output: {}", outputs.join(",")));
+        match &self.error {
+            Some(err) => lines.push(format!("error: {:?}", err)),
+            None => lines.push(format!("stop: {:?}", self.last_stop)),
+        }
+        lines.push(String::new());
+        let trace = self.trace();
+        lines.extend(
+            trace[trace.len().saturating_sub(8)..]
+                .iter()
+                .map(|entry| entry.to_string()),
+        );
+
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+        let text: Vec<String> = lines
+            .iter()
+            .map(|line| format!("{:<width$}", line))
+            .collect();
+        Frame::from(text.join("\n").as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use util::explorer::{Command, Explorer};
+
+    const COUNTDOWN: &str =
+        "Register A: 2024\nRegister B: 0\nRegister C: 0\n\nadv 3\nout A\njnz 0\n";
+
+    fn countdown() -> Debugger {
+        Debugger::new(Computer::from_str(COUNTDOWN).unwrap())
+    }
+
+    #[test]
+    fn test_step() {
+        let mut debugger = countdown().with_trace();
+        assert_eq!(debugger.step(), Ok(None));
+        assert_eq!(
+            (debugger.pc(), debugger.computer().register(Register::A)),
+            (2, 253)
+        );
+        assert_eq!(debugger.step(), Ok(None));
+        assert_eq!(debugger.outputs(), [5]);
+        assert_eq!(debugger.trace().len(), 2);
+        assert_eq!(
+            debugger.trace()[1].to_string(),
+            "    2  2: out A        A=253 B=0 C=0 out=5"
+        );
+    }
+
+    #[test]
+    fn test_breakpoints() {
+        let mut debugger = countdown()
+            .with_breakpoint(Breakpoint::Pc(0))
+            .with_breakpoint(Breakpoint::Output);
+        assert_eq!(debugger.run(), Ok(Stop::Breakpoint(Breakpoint::Output)));
+        assert_eq!(debugger.pc(), 4);
+        assert_eq!(debugger.run(), Ok(Stop::Breakpoint(Breakpoint::Pc(0))));
+        assert_eq!(debugger.cycles(), 3);
+        assert_eq!(debugger.outputs(), [5]);
+    }
+
+    #[test]
+    fn test_watches() {
+        let mut debugger = countdown().with_watch(Watch::Equals(Register::A, 0));
+        assert_eq!(
+            debugger.run(),
+            Ok(Stop::Watch(Watch::Equals(Register::A, 0)))
+        );
+        assert_eq!(debugger.outputs(), [5, 7, 3]);
+        assert_eq!(debugger.run(), Ok(Stop::Halted));
+        assert_eq!(debugger.outputs(), [5, 7, 3, 0]);
+        assert_eq!(debugger.run(), Ok(Stop::Halted));
+    }
+
+    #[test]
+    fn test_cycle_limit() {
+        let forever = "Register A: 1\nRegister B: 0\nRegister C: 0\n\nout A\njnz 0\n";
+        let computer = Computer::from_str(forever).unwrap();
+        let mut debugger = Debugger::new(computer.clone());
+        assert_eq!(debugger.run(), Err(ComputerError::CycleLimit(CYCLE_LIMIT)));
+        assert_eq!(debugger.outputs().len(), CYCLE_LIMIT / 2);
+        assert_eq!(
+            computer.part_a(),
+            Err(ComputerError::CycleLimit(CYCLE_LIMIT))
+        );
+
+        let mut debugger = Debugger::new(computer).with_cycle_limit(7);
+        assert_eq!(debugger.run(), Err(ComputerError::CycleLimit(7)));
+        assert_eq!(debugger.cycles(), 7);
+        assert_eq!(debugger.error(), Some(&ComputerError::CycleLimit(7)));
+    }
+
+    #[test]
+    fn test_explore_errors() {
+        let forever = "Register A: 1\nRegister B: 0\nRegister C: 0\n\nout A\njnz 0\n";
+        let debugger = Debugger::new(Computer::from_str(forever).unwrap()).with_cycle_limit(4);
+        let mut explorer = Explorer::new(debugger);
+        explorer.apply(&Command::Goto(100)).unwrap();
+        assert_eq!((explorer.step(), explorer.end()), (5, Some(5)));
+        assert_eq!(
+            explorer.state().error(),
+            Some(&ComputerError::CycleLimit(4))
+        );
+        assert!(explorer
+            .frame()
+            .to_string()
+            .contains("error: CycleLimit(4)"));
+        explorer.apply(&Command::Back(1)).unwrap();
+        assert_eq!(explorer.state().error(), None);
+        explorer.apply(&Command::Goto(5)).unwrap();
+        assert_eq!(
+            explorer.state().error(),
+            Some(&ComputerError::CycleLimit(4))
+        );
+
+        // A bad operand fails the same way as running out of cycles
+        let bad = Computer::from_str("Register A: 0\nRegister B: 0\nRegister C: 0\n\nProgram: 0,7")
+            .unwrap();
+        let mut explorer = Explorer::new(Debugger::new(bad));
+        explorer.apply(&Command::Goto(10)).unwrap();
+        assert_eq!(explorer.step(), 1);
+        assert!(matches!(
+            explorer.state().error(),
+            Some(ComputerError::ReservedOperand(_))
+        ));
+    }
+}
@@ -1,12 +1,45 @@
 use std::str::FromStr;
 
-use computer::Computer;
+use computer::{Computer, Register};
+use debugger::{Breakpoint, Debugger, Watch};
+use util::{explorer::Explorer, runner::Runner, tui};
 
+mod assembly;
 mod computer;
+mod computer_error;
+mod debugger;
 
 fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
-    let computer = Computer::from_str(puzzle).expect("Could not read program");
-    println!("Part A: {}", computer.clone().part_a());
-    println!("Part B: {:?}", computer.solve_input_txt());
+    let runner = Runner::from_args();
+    let puzzle = runner.read_input(util::input_path!()).expect("Unable to read puzzle input");
+    let computer = Computer::from_str(&puzzle).expect("Could not read program");
+    if runner.explore().is_some() {
+        // Stops worth running to with `until stop 0`: each pass of the loop, each output and
+        // the last pass, when A runs out, as well as any error.
+        // Stepping through by hand, a program can be left to run for far longer than when solving.
+        let debugger = Debugger::new(computer)
+            .with_cycle_limit(10 * debugger::CYCLE_LIMIT)
+            .with_trace()
+            .with_breakpoint(Breakpoint::Pc(0))
+            .with_breakpoint(Breakpoint::Output)
+            .with_watch(Watch::Equals(Register::A, 0));
+        let explorer = Explorer::new(debugger)
+            .with_metric("stop", |d: &Debugger| {
+                (d.last_stop().is_some() || d.error().is_some()) as u8 as f64
+            })
+            .with_metric("outputs", |d: &Debugger| d.outputs().len() as f64)
+            .with_metric("A", |d: &Debugger| d.computer().register(Register::A) as f64);
+        tui::explore(explorer).expect("Unable to run the explorer");
+        return;
+    }
+    runner.solve("A", || computer.part_a());
+    runner.details("A", || {
+        let listing = assembly::listing(computer.program()).expect("Could not decode program");
+        listing
+            .lines()
+            .filter_map(|line| line.split_once(": "))
+            .map(|(address, instruction)| (address.trim().to_string(), instruction.to_string()))
+            .collect::<Vec<_>>()
+    });
+    runner.solve("B", || computer.part_b());
 }
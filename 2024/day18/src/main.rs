@@ -1,6 +1,9 @@
-use std::{num::ParseIntError, str::FromStr};
+use std::{collections::HashMap, num::ParseIntError, str::FromStr};
 
-use pathfinding::{grid::Grid, prelude::dijkstra};
+use pathfinding::prelude::dijkstra;
+use util::{
+    bitgrid::BitGrid, direction::Direction, point::Point, runner::Runner, union_find::UnionFind,
+};
 
 struct MemoryRegion {
     all_cells: Vec<(usize, usize)>,
@@ -22,70 +25,116 @@ impl FromStr for MemoryRegion {
 }
 
 impl MemoryRegion {
+    fn dimensions(&self) -> (usize, usize) {
+        let (mut width, mut height) = (0, 0);
+        for cell in &self.all_cells {
+            width = width.max(cell.0 + 1);
+            height = height.max(cell.1 + 1);
+        }
+        (width, height)
+    }
+
+    // Cells are stored as (x, y), so the grid is indexed x-first.
+    fn open_neighbours(corrupted: &BitGrid, cell: Point) -> impl Iterator<Item = Point> + '_ {
+        Direction::horiz_and_vert()
+            .into_iter()
+            .map(move |d| cell.add(&d.to_point()))
+            .filter(|p| corrupted.in_bounds(p) && !corrupted.contains(p))
+    }
+
     fn part_a(&self, bytes: usize) -> Option<usize> {
-        let cells = self.all_cells.iter().copied().take(bytes).collect::<Vec<_>>();
-        let mut grid = Grid::from_coordinates(&cells)?;
-        grid.invert();
+        let (width, height) = self.dimensions();
+        let mut corrupted = BitGrid::new(width, height);
+        for &cell in self.all_cells.iter().take(bytes) {
+            corrupted.insert(&Point::from(cell));
+        }
 
-        let end = (grid.width - 1, grid.height - 1);
+        let end = Point::from((width - 1, height - 1));
         let shortest = dijkstra(
-            &(0, 0), 
+            &Point(0, 0), 
             |&p| {
-                grid.neighbours(p).into_iter().map(|v| (v, 1))
+                MemoryRegion::open_neighbours(&corrupted, p).map(|v| (v, 1))
             },
             |&p| p == end);
         shortest.map(|(_, cost)| cost)
     }
 
     fn part_b(&self, skip_bytes: usize) -> Option<(usize, usize)> {
-        let (mut width, mut height) = (0, 0);
-        for cell in &self.all_cells {
-            width = width.max(cell.0);
-            height = height.max(cell.1);
+        let (width, height) = self.dimensions();
+        let index = |p: Point| p.0 as usize * height + p.1 as usize;
+        let (start, end) = (0, index(Point::from((width - 1, height - 1))));
+
+        // Lay every byte down, then lift them again in reverse order. The first byte whose
+        // removal reconnects start and end is the one that cut them off.
+        let mut first_fall = HashMap::new();
+        for (idx, &cell) in self.all_cells.iter().enumerate() {
+            first_fall.entry(cell).or_insert(idx);
+        }
+        let mut corrupted = BitGrid::new(width, height);
+        for &cell in first_fall.keys() {
+            corrupted.insert(&Point::from(cell));
+        }
+
+        let mut sets = UnionFind::new(width * height);
+        for x in 0..width {
+            for y in 0..height {
+                let cell = Point::from((x, y));
+                if corrupted.contains(&cell) {
+                    continue;
+                }
+                for neighbour in MemoryRegion::open_neighbours(&corrupted, cell) {
+                    sets.union(index(cell), index(neighbour));
+                }
+            }
         }
-        let end = (width, height);
-        let mut grid = Grid::new(width + 1, height + 1);
-        grid.fill();
-        for cell in self.all_cells.iter().take(skip_bytes) {
-            grid.remove_vertex(*cell);
+        if sets.connected(start, end) {
+            return None;
         }
 
-        self.all_cells.iter().skip(skip_bytes).find(|&&x| {
-            grid.remove_vertex(x);
-            dijkstra(
-                &(0, 0), 
-                |&p| {
-                    grid.neighbours(p).into_iter().map(|v| (v, 1))
-                }, 
-                |&p| p == end).is_none()
-        }).copied()
+        for (idx, &cell) in self.all_cells.iter().enumerate().skip(skip_bytes).rev() {
+            if first_fall[&cell] != idx {
+                continue;
+            }
+            let point = Point::from(cell);
+            corrupted.remove(&point);
+            for neighbour in MemoryRegion::open_neighbours(&corrupted, point) {
+                sets.union(index(point), index(neighbour));
+            }
+            if sets.connected(start, end) {
+                return Some(cell);
+            }
+        }
+        self.all_cells.get(skip_bytes).copied()
     }
 }
 
 fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
-    let mem = MemoryRegion::from_str(puzzle).expect("Error parsing puzzle");
-    println!("Part A: {:?}", mem.part_a(1024));
+    let runner = Runner::from_args();
+    let puzzle = runner.read_input(util::input_path!()).expect("Unable to read puzzle input");
+    let mem = MemoryRegion::from_str(&puzzle).expect("Error parsing puzzle");
+    runner.solve("A", || mem.part_a(1024));
     // 62,32 wrong
     // 50,28 correct
-    println!("Part B: {:?}", mem.part_b(1024));
+    runner.solve("B", || mem.part_b(1024));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use util::{
+        example::{self, ExampleErr},
+        output::Answer,
+    };
 
     #[test]
-    fn test_test_txt_part_a() {
-        let puzzle = include_str!("../puzzle/test.txt");
-        let mem = MemoryRegion::from_str(puzzle).unwrap();
-        assert_eq!(Some(22), mem.part_a(12));
-    }
-
-    #[test]
-    fn test_test_txt_part_b() {
-        let puzzle = include_str!("../puzzle/test.txt");
-        let mem = MemoryRegion::from_str(puzzle).unwrap();
-        assert_eq!(Some((6, 1)), mem.part_b(0));
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            let mem = MemoryRegion::from_str(&example.puzzle).unwrap();
+            match part {
+                "A" => Ok(mem.part_a(example.param("bytes")?).render()),
+                "B" => Ok(mem.part_b(example.param("skip_bytes")?).render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
     }
 }
@@ -4,8 +4,10 @@ use std::{
 };
 
 use regex::Regex;
+use tracing::trace;
+use util::{runner::Runner, strategy::Strategies};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct TowelDesigns {
     towels: HashSet<String>,
     towel_counts: HashMap<String, usize>,
@@ -79,8 +81,8 @@ impl TowelDesigns {
 
             let leftover_design = &remaining_design[*idx..];
             let contained = self.towels.contains(check_str);
-            if contained && self.is_possible(leftover_design, &(cache.to_string() + &check_str)) {
-                let string_so_far = cache.to_string() + &check_str;
+            if contained && self.is_possible(leftover_design, &(cache.to_string() + check_str)) {
+                let string_so_far = cache.to_string() + check_str;
                 self.towels.insert(string_so_far);
                 self.towels.insert(leftover_design.to_string());
                 return true;
@@ -103,11 +105,11 @@ impl TowelDesigns {
             .map(|d| d.to_string())
             .collect();
 
-        // println!("Design {} starts with {:?}", design, matching_towels);
+        trace!(design, ?matching_towels, "matching prefixes");
         matching_towels.iter().any(|t| {
             // Cache this possible towel arrangement
             let total_so_far = &(already_checked.to_owned() + t);
-            self.insert_cache(&total_so_far);
+            self.insert_cache(total_so_far);
 
             // let remaining = &design[t.len()..];
             if self.is_possible_alt(&design[t.len()..], total_so_far) {
@@ -122,38 +124,6 @@ impl TowelDesigns {
         // }
     }
 
-    // Attempt going RTL on string by turning it into a stack.
-    // Not exhaustive, hence gave the wrong solution.
-    fn is_possible_alt2(&mut self, design: &str) -> bool {
-        let mut stack: Vec<char> = design.chars().collect();
-        let mut check_str = "".to_string();
-
-        if self.towels.contains(design) {
-            return true;
-        }
-
-        // This is not exhaustive - it prioritises smaller segments
-        // TODO Need some backtracking to check alternative possibilities
-        while let Some(c) = stack.pop() {
-            check_str = c.to_string() + &check_str;
-            println!("Check string is now {}", check_str);
-            if self.towels.contains(&check_str) {
-                check_str.clear();
-            }
-            if check_str.len() > self.max_towel_length {
-                stack.extend(check_str.chars().take(check_str.len() - 1));
-                check_str.clear();
-                continue;
-            }
-        }
-
-        if check_str.len() > 0 {
-            println!("{}", check_str);
-            return self.towels.contains(&check_str);
-        }
-        true
-    }
-
     // Function works, slower than the dynamic programming solution.
     // Based on regex hint from Advent of Code subreddit.
     fn is_possible_alt3(&self, design: &str) -> bool {
@@ -164,25 +134,11 @@ impl TowelDesigns {
         re.is_match(design)
     }
 
-    // Function does not work.
-    fn count_possibilities(&self, design: &str) -> usize {
-        let v: Vec<String> = self.towels.iter().map(|s| s.to_string()).collect();
-        let inner: String = v.join("|");
-        let pattern = format!(r"^({})+$", inner);
-        println!("Patt: {}", pattern);
-        let re = Regex::new(&pattern).unwrap();
-        println!("Design {}.", design);
-        for m in re.find_iter(design) {
-            println!("{:?}", m);
-        }
-        0
-    }
-
     fn count_possibilities_alt(&self, design: &str) -> usize {
         let mut counts = HashMap::new();
         counts.insert(0, 1);
         for idx in 1..=design.len() {
-            let lower = 0.max(idx.saturating_sub(self.max_towel_length));
+            let lower = idx.saturating_sub(self.max_towel_length);
             for inner in lower..=idx {
                 if self.towels.contains(&design[inner..idx]) {
                     *counts.entry(idx).or_insert(0) += *counts.get(&inner).unwrap_or(&0);
@@ -200,6 +156,7 @@ impl TowelDesigns {
 
         // Return entry from cache if already existing
         if let Some(count) = self.towel_counts.get(design) {
+            trace!(design, count, "cache hit");
             return *count;
         }
 
@@ -232,30 +189,76 @@ impl TowelDesigns {
     }
 }
 
+fn part_a_strategies(towels: &TowelDesigns) -> Strategies<'_, usize> {
+    let count_possible = |is_possible: fn(&mut TowelDesigns, &str) -> bool| {
+        let mut towels = towels.clone();
+        let designs = towels.designs.clone();
+        designs.iter().filter(|d| is_possible(&mut towels, d)).count()
+    };
+    Strategies::new("A")
+        .register("regex", move || towels.clone().part_a())
+        .register("dp", move || count_possible(|t, d| t.count_possibilities_alt(d) > 0))
+        .register("memo", move || count_possible(|t, d| t.count_possibilities_alt2(d) > 0))
+        .register_slow("prefix", move || count_possible(|t, d| t.is_possible_alt(d, &String::new())))
+        .register_slow("first", move || count_possible(|t, d| t.is_possible(d, "")))
+}
+
+fn part_b_strategies(towels: &TowelDesigns) -> Strategies<'_, usize> {
+    Strategies::new("B")
+        .register("memo", move || towels.clone().part_b())
+        .register("dp", move || {
+            towels
+                .designs
+                .iter()
+                .map(|d| towels.count_possibilities_alt(d))
+                .sum()
+        })
+}
+
 fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
-    let mut towels = TowelDesigns::from_str(puzzle).expect("Could not parse towel designs");
-    println!("Part A: {}", towels.part_a());
-    println!("Part B: {}", towels.part_b());
+    let runner = Runner::from_args();
+    let puzzle = runner.read_input(util::input_path!()).expect("Unable to read puzzle input");
+    let towels = TowelDesigns::from_str(&puzzle).expect("Could not parse towel designs");
+    runner.run(&part_a_strategies(&towels));
+    runner.run(&part_b_strategies(&towels));
+    runner.details("B", || {
+        let mut towels = towels.clone();
+        let designs = towels.designs.clone();
+        designs
+            .into_iter()
+            .map(|d| {
+                let count = towels.count_possibilities_alt2(&d);
+                (d, count)
+            })
+            .collect::<Vec<_>>()
+    });
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use util::{
+        example::{self, ExampleErr},
+        output::Answer,
+    };
 
     #[test]
-    fn test_test_txt_part_a() {
+    fn test_test_txt_strategies_agree() {
         let puzzle = include_str!("../puzzle/test.txt");
-        let mut towels = TowelDesigns::from_str(puzzle).unwrap();
-        println!("{:?}", towels);
-        assert_eq!(towels.part_a(), 6);
+        let towels = TowelDesigns::from_str(puzzle).unwrap();
+        assert!(part_a_strategies(&towels).cross_check().is_ok());
+        assert!(part_b_strategies(&towels).cross_check().is_ok());
     }
 
     #[test]
-    fn test_test_txt_part_b() {
-        let puzzle = include_str!("../puzzle/test.txt");
-        let mut towels = TowelDesigns::from_str(puzzle).unwrap();
-        println!("{:?}", towels);
-        assert_eq!(towels.part_b(), 16);
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            let mut towels = TowelDesigns::from_str(&example.puzzle).unwrap();
+            match part {
+                "A" => Ok(towels.part_a().render()),
+                "B" => Ok(towels.part_b().render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
     }
 }
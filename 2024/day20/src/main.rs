@@ -7,6 +7,7 @@ use pathfinding::{
     grid::Grid,
     prelude::{dijkstra, dijkstra_reach},
 };
+use util::{runner::Runner, strategy::Strategies};
 
 #[derive(Debug)]
 struct RaceMaze {
@@ -15,7 +16,7 @@ struct RaceMaze {
     end: (usize, usize),
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 enum RaceMazeErr {
     NoStartFound,
     NoEndFound,
@@ -257,30 +258,61 @@ impl RaceMaze {
     }
 }
 
+type Answer = Result<usize, RaceMazeErr>;
+
+// Part A is part B with a two-picosecond cheat, so the general solver is the default. The
+// wall-removal search reruns Dijkstra per wall and takes tens of seconds on the real input.
+fn part_a_strategies(maze: &RaceMaze, threshold: usize) -> Strategies<'_, Answer> {
+    Strategies::new("A")
+        .register("general", move || maze.part_b(2, threshold))
+        .register("alt", move || maze.part_a_alt(threshold))
+        .register_slow("walls", move || maze.part_a(threshold))
+}
+
+fn part_b_strategies(maze: &RaceMaze, threshold: usize) -> Strategies<'_, Answer> {
+    Strategies::new("B").register("general", move || maze.part_b(20, threshold))
+}
+
 fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
-    let maze = RaceMaze::from_str(puzzle).expect("Failed to parse puzzle");
-    // println!("Part A: {:?}", maze.part_a(100));
-    // println!("Part A alt: {:?}", maze.part_a_alt(100));
-    println!("Part A alt2: {:?}", maze.part_b(2, 100));
-    println!("Part B: {:?}", maze.part_b(20, 100));
+    let runner = Runner::from_args();
+    let puzzle = runner.read_input(util::input_path!()).expect("Unable to read puzzle input");
+    let maze = RaceMaze::from_str(&puzzle).expect("Failed to parse puzzle");
+    runner.run(&part_a_strategies(&maze, 100));
+    runner.run(&part_b_strategies(&maze, 100));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use util::{
+        example::{self, ExampleErr},
+        output::Answer as _,
+    };
 
     #[test]
-    fn test_test_txt_part_a() {
+    fn test_test_txt_strategies_agree() {
         let puzzle = include_str!("../puzzle/test.txt");
         let maze = RaceMaze::from_str(puzzle).unwrap();
-        assert_eq!(maze.part_a_alt(36).unwrap(), 4);
+        for threshold in [2, 20, 36, 64] {
+            assert!(part_a_strategies(&maze, threshold).cross_check().is_ok());
+        }
     }
 
     #[test]
-    fn test_test_txt_part_b() {
-        let puzzle = include_str!("../puzzle/test.txt");
-        let maze = RaceMaze::from_str(puzzle).unwrap();
-        assert_eq!(maze.part_b(20, 50).unwrap(), 285);
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            let maze = RaceMaze::from_str(&example.puzzle).unwrap();
+            match part {
+                "A" => {
+                    let strategies = part_a_strategies(&maze, example.param("threshold_a")?);
+                    Ok(strategies.run_default().render())
+                }
+                "B" => {
+                    let strategies = part_b_strategies(&maze, example.param("threshold_b")?);
+                    Ok(strategies.run_default().render())
+                }
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
     }
 }
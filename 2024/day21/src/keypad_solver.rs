@@ -1,6 +1,7 @@
 use std::{collections::HashMap, str::FromStr};
 
 use itertools::Itertools;
+use tracing::trace;
 
 use crate::{robot::Robot, robot_parse_err::RobotParseErr};
 
@@ -81,6 +82,7 @@ impl KeypadSolver {
     ) -> Option<usize> {
 
         if let Some(x) = self.cache.get(&(robot_level, prev_char, target)){
+            trace!(robot_level, %prev_char, %target, presses = x, "cache hit");
             return Some(*x);
         }
 
@@ -1,13 +1,56 @@
 use keypad_solver::KeypadSolver;
+use util::{runner::Runner, strategy::Strategies};
 
 mod keypad_solver;
 mod robot;
 mod robot_parse_err;
 
+fn part_a_strategies(puzzle: &str) -> Strategies<'_, usize> {
+    let solver = || KeypadSolver::new_part_a().expect("Could not create simple key solver");
+    Strategies::new("A")
+        .register("memo", move || solver().sum_keytaps_alt(puzzle))
+        .register("paths", move || solver().sum_keytaps(puzzle))
+}
+
+// Expanding every path through 25 robots is out of reach, so part B only has the cached search.
+fn part_b_strategies(puzzle: &str) -> Strategies<'_, usize> {
+    Strategies::new("B").register("memo", move || {
+        let mut complex_solver =
+            KeypadSolver::new_part_b().expect("Could not create complex key solver");
+        complex_solver.sum_keytaps_alt(puzzle)
+    })
+}
+
 fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
-    let mut simple_solver = KeypadSolver::new_part_a().expect("Could not create simple key solver");
-    println!("Part A: {}", simple_solver.sum_keytaps_alt(puzzle));
-    let mut complex_solver = KeypadSolver::new_part_b().expect("Could not create complex key solver");
-    println!("Part B: {}", complex_solver.sum_keytaps_alt(puzzle));
+    let runner = Runner::from_args();
+    let puzzle = runner.read_input(util::input_path!()).expect("Unable to read puzzle input");
+    runner.run(&part_a_strategies(&puzzle));
+    runner.run(&part_b_strategies(&puzzle));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use util::{
+        example::{self, ExampleErr},
+        output::Answer,
+    };
+
+    #[test]
+    fn test_test_txt_strategies_agree() {
+        let puzzle = include_str!("../puzzle/test.txt");
+        let outcomes = part_a_strategies(puzzle).cross_check().unwrap();
+        assert_eq!(126384, outcomes[0].answer);
+    }
+
+    #[test]
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            match part {
+                "A" => Ok(part_a_strategies(&example.puzzle).run_default().render()),
+                "B" => Ok(part_b_strategies(&example.puzzle).run_default().render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
+    }
 }
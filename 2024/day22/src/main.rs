@@ -1,8 +1,10 @@
 use std::{
     collections::{HashMap, LinkedList},
-    num::ParseIntError,
+    io::BufRead,
 };
 
+use util::runner::Runner;
+
 const DENOM: usize = 16777216;
 type DiffKey = (i32, i32, i32, i32);
 
@@ -49,56 +51,67 @@ fn make_diffs_dict(secret: usize) -> HashMap<DiffKey, i32> {
     diffs
 }
 
-fn part_a(puzzle: &str) -> Result<usize, ParseIntError> {
-    let secrets: Result<Vec<_>, ParseIntError> = puzzle.lines().map(|line| line.parse()).collect();
-    Ok(secrets?.iter().map(|n| secret_after_n(*n)).sum())
+#[derive(Debug)]
+enum MarketErr {
+    ReadFailed,
+    SecretParseFailed,
+}
+
+/// Every buyer's secret after the day's trading and the bananas each price-change sequence
+/// would buy in total, gathered a buyer at a time so that the secrets are never all held.
+#[derive(Debug, Default)]
+struct Market {
+    secret_sum: usize,
+    bananas: HashMap<DiffKey, i32>,
 }
 
-fn part_b(puzzle: &str) -> Option<i32> {
-    let secrets: Vec<usize> = puzzle
-        .lines()
-        .filter_map(|line| line.parse::<usize>().ok())
-        .collect();
+impl Market {
+    fn from_reader<R: BufRead>(reader: R) -> Result<Market, MarketErr> {
+        let mut market = Market::default();
+        for line in reader.lines() {
+            let secret: usize = line
+                .map_err(|_| MarketErr::ReadFailed)?
+                .parse()
+                .map_err(|_| MarketErr::SecretParseFailed)?;
+            market.secret_sum += secret_after_n(secret);
+            make_diffs_dict(secret)
+                .iter()
+                .for_each(|(key, val)| *market.bananas.entry(*key).or_insert(0) += *val);
+        }
+
+        Ok(market)
+    }
 
-    let mut all_diffs = HashMap::new();
-    secrets.iter().for_each(|secret| {
-        make_diffs_dict(*secret)
-            .iter()
-            .for_each(|(key, val)| *all_diffs.entry(*key).or_insert(0) += *val);
-    });
+    fn part_a(&self) -> usize {
+        self.secret_sum
+    }
 
-    all_diffs.values().max().copied()
+    fn part_b(&self) -> Option<i32> {
+        self.bananas.values().max().copied()
+    }
 }
 
 fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
-    println!("Part A: {:?}", part_a(puzzle));
-    println!("Part B: {:?}", part_b(puzzle));
+    let runner = Runner::from_args();
+    let input = runner.open_input(util::input_path!()).expect("Unable to open puzzle input");
+    let market = Market::from_reader(input).expect("Unable to read puzzle input");
+    runner.solve("A", || market.part_a());
+    runner.solve("B", || market.part_b());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    #[test]
-    fn test_test_txt_part_a() {
-        let puzzle = include_str!("../puzzle/test.txt");
-        let result = part_a(puzzle);
-        assert_eq!(Ok(37327623), result);
-    }
-
-    #[test]
-    fn test_test_part_b_txt() {
-        let puzzle = include_str!("../puzzle/test_part_b.txt");
-        let result = part_b(puzzle);
-        assert_eq!(Some(23), result);
-    }
+    use util::{
+        example::{self, ExampleErr},
+        output::Answer,
+    };
 
     // 8685429
     #[test]
     fn test_starting_from_1() {
-        let puzzle = "1";
-        let result = part_a(puzzle);
-        assert_eq!(Ok(8685429), result);
+        let market = Market::from_reader("1".as_bytes()).unwrap();
+        assert_eq!(8685429, market.part_a());
     }
 
     #[test]
@@ -121,4 +134,16 @@ mod tests {
         let actual = diffs.get(&(-2, 1, -1, 3)).copied();
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            let market = Market::from_reader(example.puzzle.as_bytes()).unwrap();
+            match part {
+                "A" => Ok(market.part_a().render()),
+                "B" => Ok(market.part_b().render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
+    }
 }
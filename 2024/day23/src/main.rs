@@ -4,6 +4,7 @@ use std::{
 };
 
 use itertools::Itertools;
+use util::{runner::Runner, strategy::Strategies};
 
 struct LANParty {
     network: HashMap<String, Vec<String>>,
@@ -80,7 +81,7 @@ impl LANParty {
                 // Got to here? Network is okay, add it
                 let mut full_net: Vec<_> = vec![key]
                     .into_iter()
-                    .chain(candidate_network.into_iter())
+                    .chain(candidate_network)
                     .collect();
                 full_net.sort_unstable();
                 full_networks.insert(full_net);
@@ -115,7 +116,7 @@ impl LANParty {
                 // Got to here? Network is okay, add it
                 let mut full_net: Vec<_> = vec![key]
                     .into_iter()
-                    .chain(candidate_network.into_iter())
+                    .chain(candidate_network)
                     .collect();
                 full_net.sort_unstable();
                 biggest_net = full_net;
@@ -125,36 +126,55 @@ impl LANParty {
     }
 }
 
+fn part_a_strategies(party: &LANParty) -> Strategies<'_, usize> {
+    Strategies::new("A").register("triples", move || party.part_a())
+}
+
+fn part_b_strategies(party: &LANParty) -> Strategies<'_, Option<String>> {
+    Strategies::new("B")
+        .register("pruned", move || Some(party.part_b_alt()).filter(|s| !s.is_empty()))
+        .register("all-sets", move || party.part_b())
+}
+
 fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
-    let party = LANParty::from_str(puzzle).expect("Could not parse puzzle");
-    println!("Part A: {}", party.part_a());
-    println!("Part B: {:?}", party.part_b());
-    println!("Part B: {:?}", party.part_b_alt());
+    let runner = Runner::from_args();
+    let puzzle = runner.read_input(util::input_path!()).expect("Unable to read puzzle input");
+    let party = LANParty::from_str(&puzzle).expect("Could not parse puzzle");
+    runner.run(&part_a_strategies(&party));
+    runner.run(&part_b_strategies(&party));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use util::{
+        example::{self, ExampleErr},
+        output::Answer,
+    };
 
     #[test]
-    fn test_test_txt_part_a() {
+    fn test_test_txt_part_b_alt() {
         let puzzle = include_str!("../puzzle/test.txt");
         let party = LANParty::from_str(puzzle).unwrap();
-        assert_eq!(7, party.part_a());
+        assert_eq!("co,de,ka,ta".to_string(), party.part_b_alt());
     }
 
     #[test]
-    fn test_test_txt_part_b() {
+    fn test_test_txt_strategies_agree() {
         let puzzle = include_str!("../puzzle/test.txt");
         let party = LANParty::from_str(puzzle).unwrap();
-        assert_eq!(Some("co,de,ka,ta".to_string()), party.part_b());
+        assert!(part_b_strategies(&party).cross_check().is_ok());
     }
 
     #[test]
-    fn test_test_txt_part_b_alt() {
-        let puzzle = include_str!("../puzzle/test.txt");
-        let party = LANParty::from_str(puzzle).unwrap();
-        assert_eq!("co,de,ka,ta".to_string(), party.part_b_alt());
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            let party = LANParty::from_str(&example.puzzle).unwrap();
+            match part {
+                "A" => Ok(party.part_a().render()),
+                "B" => Ok(party.part_b().render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
     }
 }
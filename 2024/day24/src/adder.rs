@@ -0,0 +1,238 @@
+//! Checks a circuit meant to add the `x` and `y` buses into `z` against the shape of a
+//! ripple-carry adder, and works out which gate outputs have been swapped.
+//!
+//! Bit 0 is a half adder, `x00 XOR y00 -> z00` with `x00 AND y00` as its carry. Every later bit
+//! is a full adder: the half sum `x XOR y` is XORed with the incoming carry to give `z`, and the
+//! two ANDs (`x AND y`, half sum AND carry) are ORed into the next carry. The final carry is the
+//! top `z` bit. A wire whose gate does not fit where it is used breaks one of the `Rule`s, and the
+//! suspects are paired up by trying each pairing against simulated additions.
+
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::{simulation::Simulator, CrossedWires, Gate, WireSource};
+
+/// Pairing up more suspects than this is too slow to try every way.
+const MAX_SUSPECTS: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    SumNotXor,
+    CarryOutNotOr,
+    SumNotOutput,
+    HalfSumUnused,
+    CarryTermUnused,
+}
+
+impl Rule {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Rule::SumNotXor => "z output not driven by XOR",
+            Rule::CarryOutNotOr => "top z output not driven by the final carry OR",
+            Rule::SumNotOutput => "sum XOR does not drive a z output",
+            Rule::HalfSumUnused => "x XOR y does not feed a sum XOR",
+            Rule::CarryTermUnused => "AND does not feed a carry OR",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fault {
+    pub wire: String,
+    pub rule: Rule,
+}
+
+#[derive(Debug)]
+pub struct AdderReport {
+    pub faults: Vec<Fault>,
+    /// Swaps that make the circuit add, or `None` if no pairing of the faults does.
+    pub swaps: Option<Vec<(String, String)>>,
+}
+
+fn is_input(wire: &str) -> bool {
+    wire.starts_with('x') || wire.starts_with('y')
+}
+
+/// Every wire driven by a gate that does not fit the adder, in wire order.
+pub fn faults(circuit: &CrossedWires) -> Vec<Fault> {
+    let last_z = format!("z{:02}", circuit.bus_width('x'));
+    let mut readers: HashMap<&String, Vec<&WireSource>> = HashMap::new();
+    for source in circuit.wires.values() {
+        for input in source.inputs() {
+            readers.entry(input).or_default().push(source);
+        }
+    }
+    let feeds = |wire: &String, kind: fn(&WireSource) -> bool| {
+        readers
+            .get(wire)
+            .is_some_and(|sources| sources.iter().any(|source| kind(source)))
+    };
+    let is_xor = |source: &WireSource| source.is(Gate::Xor);
+    let is_or = |source: &WireSource| source.is(Gate::Or);
+
+    let mut faults = vec![];
+    for (wire, source) in circuit.wires.iter().sorted_by_key(|(wire, _)| *wire) {
+        if let WireSource::Const(_) = source {
+            continue;
+        }
+        let inputs = source.inputs();
+        let from_inputs = inputs.iter().all(|input| is_input(input));
+        let first_bit = from_inputs && inputs.iter().any(|input| input.ends_with("00"));
+        let is_z = wire.starts_with('z');
+
+        let rule = if *wire == last_z && !is_or(source) {
+            Some(Rule::CarryOutNotOr)
+        } else if is_z && *wire != last_z && !is_xor(source) {
+            Some(Rule::SumNotXor)
+        } else if is_xor(source) && !from_inputs && !is_z {
+            Some(Rule::SumNotOutput)
+        } else if is_xor(source) && from_inputs && !first_bit && !feeds(wire, is_xor) {
+            Some(Rule::HalfSumUnused)
+        } else if source.is(Gate::And) && !first_bit && !feeds(wire, is_or) {
+            Some(Rule::CarryTermUnused)
+        } else {
+            None
+        };
+        if let Some(rule) = rule {
+            faults.push(Fault {
+                wire: wire.to_string(),
+                rule,
+            });
+        }
+    }
+    faults
+}
+
+/// Whether the circuit adds, with `z` one bit wider than the operands for the carry.
+pub fn adds_correctly(circuit: &CrossedWires) -> bool {
+    let width = circuit.bus_width('x');
+    if width == 0 || circuit.bus_width('y') != width || circuit.bus_width('z') != width + 1 {
+        return false;
+    }
+    Simulator::new(circuit).is_ok_and(|mut simulator| simulator.check(|x, y| x + y).is_ok())
+}
+
+/// Tries every way of pairing up the remaining suspects, returning the first that adds.
+fn pair_up(
+    circuit: &CrossedWires,
+    remaining: &[&String],
+    pairs: &mut Vec<(String, String)>,
+) -> Option<Vec<(String, String)>> {
+    let Some((first, rest)) = remaining.split_first() else {
+        return adds_correctly(&circuit.swapped(pairs)).then(|| pairs.clone());
+    };
+    for idx in 0..rest.len() {
+        pairs.push((first.to_string(), rest[idx].to_string()));
+        let others: Vec<&String> = [&rest[..idx], &rest[idx + 1..]].concat();
+        if let Some(found) = pair_up(circuit, &others, pairs) {
+            return Some(found);
+        }
+        pairs.pop();
+    }
+    None
+}
+
+pub fn repair(circuit: &CrossedWires) -> AdderReport {
+    let faults = faults(circuit);
+    let suspects: Vec<&String> = faults.iter().map(|fault| &fault.wire).collect();
+    let swaps = match suspects.len().is_multiple_of(2) && suspects.len() <= MAX_SUSPECTS {
+        true => pair_up(circuit, &suspects, &mut vec![]),
+        false => None,
+    };
+    AdderReport { faults, swaps }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use util::input;
+
+    /// A correct ripple-carry adder, with `s` half sums, `a` and `b` carry terms and `c` carries.
+    fn ripple_adder(width: usize) -> CrossedWires {
+        let mut lines = vec![];
+        for bit in 0..width {
+            lines.push(format!("x{:02}: 0", bit));
+            lines.push(format!("y{:02}: 0", bit));
+        }
+        lines.push(String::new());
+        lines.push("x00 XOR y00 -> z00".to_string());
+        lines.push("x00 AND y00 -> c00".to_string());
+        for bit in 1..width {
+            let carry = match bit + 1 == width {
+                true => format!("z{:02}", width),
+                false => format!("c{:02}", bit),
+            };
+            lines.push(format!("x{0:02} XOR y{0:02} -> s{0:02}", bit));
+            lines.push(format!("x{0:02} AND y{0:02} -> a{0:02}", bit));
+            lines.push(format!("s{0:02} XOR c{1:02} -> z{0:02}", bit, bit - 1));
+            lines.push(format!("s{0:02} AND c{1:02} -> b{0:02}", bit, bit - 1));
+            lines.push(format!("a{0:02} OR b{0:02} -> {1}", bit, carry));
+        }
+        CrossedWires::from_str(&lines.join("\n")).unwrap()
+    }
+
+    fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(l, r)| (l.to_string(), r.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_correct_adder() {
+        let circuit = ripple_adder(8);
+        assert!(adds_correctly(&circuit));
+        let report = repair(&circuit);
+        assert_eq!(report.faults, vec![]);
+        assert_eq!(report.swaps, Some(vec![]));
+    }
+
+    #[test]
+    fn test_faults() {
+        let circuit = ripple_adder(8).swapped(&pairs(&[("s04", "a04"), ("z06", "c06")]));
+        assert!(!adds_correctly(&circuit));
+        let found: Vec<_> = faults(&circuit)
+            .into_iter()
+            .map(|fault| (fault.wire, fault.rule))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("a04".to_string(), Rule::HalfSumUnused),
+                ("c06".to_string(), Rule::SumNotOutput),
+                ("s04".to_string(), Rule::CarryTermUnused),
+                ("z06".to_string(), Rule::SumNotXor),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_repair() {
+        let swaps = pairs(&[
+            ("a03", "s03"),
+            ("b05", "z05"),
+            ("c07", "z07"),
+            ("c10", "z11"),
+        ]);
+        let circuit = ripple_adder(12).swapped(&swaps);
+        let report = repair(&circuit);
+        assert_eq!(report.faults.len(), 8);
+        let mut found = report.swaps.unwrap();
+        found.sort();
+        assert_eq!(found, swaps);
+        assert!(adds_correctly(&circuit.swapped(&found)));
+    }
+
+    #[test]
+    fn test_repair_real_input() {
+        let Some(puzzle) = input::real_input(env!("CARGO_MANIFEST_DIR")) else {
+            return;
+        };
+        let circuit = CrossedWires::from_str(&puzzle).unwrap();
+        let swaps = repair(&circuit).swaps.unwrap();
+        assert_eq!(swaps.len(), 4);
+        assert!(adds_correctly(&circuit.swapped(&swaps)));
+    }
+}
@@ -0,0 +1,199 @@
+//! Evaluates a circuit in topological order, so each wire is computed once per set of inputs
+//! however many gates read it, and a circuit that feeds back into itself is refused up front.
+
+use std::collections::{HashMap, VecDeque};
+
+use num_bigint::BigUint;
+
+use crate::{CrossedWires, WireSource};
+
+#[derive(Debug, PartialEq)]
+pub enum EvalErr {
+    /// A gate reads a wire nothing drives: the missing wire, then the gate's output.
+    Undriven(String, String),
+    /// Wires that each depend on the next, the last on the first.
+    Cycle(Vec<String>),
+}
+
+pub struct Evaluator {
+    names: Vec<String>,
+    sources: Vec<WireSource>,
+    inputs: Vec<Vec<usize>>,
+    /// Every gate, each after all the gates it reads.
+    order: Vec<usize>,
+    /// Each bus's wires by bit, lowest first.
+    buses: HashMap<char, Vec<(u64, usize)>>,
+    values: Vec<u64>,
+}
+
+impl Evaluator {
+    pub fn new(circuit: &CrossedWires) -> Result<Evaluator, EvalErr> {
+        let mut names: Vec<String> = circuit.wires.keys().cloned().collect();
+        names.sort();
+        let index: HashMap<&String, usize> = names
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| (name, idx))
+            .collect();
+        let sources: Vec<WireSource> = names
+            .iter()
+            .map(|name| circuit.wires[name].clone())
+            .collect();
+
+        let mut inputs = vec![];
+        for (name, source) in names.iter().zip(sources.iter()) {
+            let wires = source.inputs().into_iter().map(|input| {
+                index
+                    .get(input)
+                    .copied()
+                    .ok_or_else(|| EvalErr::Undriven(input.to_string(), name.to_string()))
+            });
+            inputs.push(wires.collect::<Result<Vec<usize>, EvalErr>>()?);
+        }
+
+        let mut buses: HashMap<char, Vec<(u64, usize)>> = HashMap::new();
+        for (idx, name) in names.iter().enumerate() {
+            let Some(prefix) = name.chars().next() else {
+                continue;
+            };
+            if let Some(bit) = CrossedWires::bus_bit(name, prefix) {
+                buses.entry(prefix).or_default().push((bit, idx));
+            }
+        }
+        buses.values_mut().for_each(|bus| bus.sort());
+
+        let values = sources
+            .iter()
+            .map(|source| match source {
+                WireSource::Const(value) => *value,
+                _ => 0,
+            })
+            .collect();
+        let mut evaluator = Evaluator {
+            names,
+            sources,
+            inputs,
+            order: vec![],
+            buses,
+            values,
+        };
+        evaluator.order = evaluator.sort()?;
+        Ok(evaluator)
+    }
+
+    /// Orders the gates so each comes after everything it reads. Whatever cannot be ordered
+    /// lies on or behind a cycle, and following inputs among those wires finds one.
+    fn sort(&self) -> Result<Vec<usize>, EvalErr> {
+        let mut readers: Vec<Vec<usize>> = vec![vec![]; self.names.len()];
+        let mut unresolved: Vec<usize> = self.inputs.iter().map(|inputs| inputs.len()).collect();
+        for (idx, inputs) in self.inputs.iter().enumerate() {
+            inputs.iter().for_each(|&input| readers[input].push(idx));
+        }
+
+        let mut ready: VecDeque<usize> = (0..self.names.len())
+            .filter(|&idx| unresolved[idx] == 0)
+            .collect();
+        let mut order = vec![];
+        while let Some(idx) = ready.pop_front() {
+            if !self.inputs[idx].is_empty() {
+                order.push(idx);
+            }
+            for &reader in readers[idx].iter() {
+                unresolved[reader] -= 1;
+                if unresolved[reader] == 0 {
+                    ready.push_back(reader);
+                }
+            }
+        }
+
+        let Some(start) = (0..self.names.len()).find(|&idx| unresolved[idx] > 0) else {
+            return Ok(order);
+        };
+        let mut path = vec![start];
+        loop {
+            let last = *path.last().unwrap_or(&start);
+            let next = self.inputs[last]
+                .iter()
+                .copied()
+                .find(|&input| unresolved[input] > 0)
+                .unwrap_or(start);
+            if let Some(pos) = path.iter().position(|&idx| idx == next) {
+                let cycle = path[pos..].iter().map(|&idx| self.names[idx].clone());
+                return Err(EvalErr::Cycle(cycle.collect()));
+            }
+            path.push(next);
+        }
+    }
+
+    /// Sets the constant wires of a bus to the bits of `value`, ignoring bits it has no wire for.
+    pub fn set_bus(&mut self, prefix: char, value: &BigUint) {
+        for &(bit, idx) in self.buses.get(&prefix).into_iter().flatten() {
+            if let WireSource::Const(_) = self.sources[idx] {
+                self.values[idx] = value.bit(bit) as u64;
+            }
+        }
+    }
+
+    /// Computes every gate once from the current constants.
+    pub fn run(&mut self) {
+        for &idx in self.order.iter() {
+            let inputs = self.inputs[idx].iter().map(|&input| self.values[input]);
+            self.values[idx] = self.sources[idx].apply(inputs);
+        }
+    }
+
+    /// A bus's values as of the last run, lowest bit first.
+    pub fn bits(&self, prefix: char) -> Vec<bool> {
+        let bus = self.buses.get(&prefix).into_iter().flatten();
+        bus.map(|&(_, idx)| self.values[idx] == 1).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn circuit(gates: &[String]) -> CrossedWires {
+        let puzzle = format!("x00: 1\ny00: 0\n\n{}", gates.join("\n"));
+        CrossedWires::from_str(&puzzle).unwrap()
+    }
+
+    #[test]
+    fn test_errors() {
+        let undriven = circuit(&["x00 AND w00 -> z00".to_string()]);
+        assert_eq!(
+            Evaluator::new(&undriven).err(),
+            Some(EvalErr::Undriven("w00".to_string(), "z00".to_string()))
+        );
+
+        let looped = circuit(&[
+            "x00 AND abc -> z00".to_string(),
+            "z00 OR y00 -> def".to_string(),
+            "def XOR x00 -> abc".to_string(),
+            "abc OR y00 -> z01".to_string(),
+        ]);
+        let Some(EvalErr::Cycle(mut wires)) = Evaluator::new(&looped).err() else {
+            panic!("cycle not found");
+        };
+        wires.sort();
+        assert_eq!(wires, ["abc", "def", "z00"]);
+    }
+
+    #[test]
+    fn test_shared_wires() {
+        // Each wire reads the previous one twice, so re-evaluating shared wires would take 2^n
+        // steps and recursing would take n stack frames.
+        let mut gates = vec!["x00 OR y00 -> w00000".to_string()];
+        for idx in 1..20_000 {
+            gates.push(format!("w{0:05} XOR w{0:05} -> w{1:05}", idx - 1, idx));
+        }
+        gates.push("w19999 OR x00 -> z00".to_string());
+        let mut evaluator = Evaluator::new(&circuit(&gates)).unwrap();
+        evaluator.run();
+        assert_eq!(evaluator.bits('z'), [true]);
+        evaluator.set_bus('x', &BigUint::ZERO);
+        evaluator.run();
+        assert_eq!(evaluator.bits('z'), [false]);
+    }
+}
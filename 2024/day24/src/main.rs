@@ -1,29 +1,143 @@
+use evaluator::{EvalErr, Evaluator};
 use itertools::Itertools;
-use std::{
-    collections::HashMap,
-    fs::File,
-    io::{BufWriter, Write},
-    str::FromStr,
-};
-
-#[derive(Debug, Clone)]
+use simulation::Simulator;
+use std::{collections::HashMap, str::FromStr};
+use util::runner::Runner;
+
+mod adder;
+mod evaluator;
+mod netlist;
+mod simulation;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Gate {
+    And,
+    Or,
+    Xor,
+    Nand,
+    Nor,
+    Xnor,
+    Not,
+}
+
+impl Gate {
+    const ALL: [Gate; 7] = [
+        Gate::And,
+        Gate::Or,
+        Gate::Xor,
+        Gate::Nand,
+        Gate::Nor,
+        Gate::Xnor,
+        Gate::Not,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Gate::And => "AND",
+            Gate::Or => "OR",
+            Gate::Xor => "XOR",
+            Gate::Nand => "NAND",
+            Gate::Nor => "NOR",
+            Gate::Xnor => "XNOR",
+            Gate::Not => "NOT",
+        }
+    }
+
+    /// Whether the gate can take this many inputs: `NOT` takes one, the rest two or more.
+    fn accepts(&self, inputs: usize) -> bool {
+        match self {
+            Gate::Not => inputs == 1,
+            _ => inputs >= 2,
+        }
+    }
+
+    fn apply<I: Iterator<Item = u64>>(&self, inputs: I) -> u64 {
+        match self {
+            Gate::And => inputs.fold(1, |acc, value| acc & value),
+            Gate::Or => inputs.fold(0, |acc, value| acc | value),
+            Gate::Xor => inputs.fold(0, |acc, value| acc ^ value),
+            Gate::Nand | Gate::Not => 1 ^ Gate::And.apply(inputs),
+            Gate::Nor => 1 ^ Gate::Or.apply(inputs),
+            Gate::Xnor => 1 ^ Gate::Xor.apply(inputs),
+        }
+    }
+}
+
+impl FromStr for Gate {
+    type Err = CrossedWiresParseErr;
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Gate::ALL
+            .into_iter()
+            .find(|gate| gate.name() == name)
+            .ok_or(CrossedWiresParseErr::UnknownGate(name.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 enum WireSource {
-    And(String, String),
-    Or(String, String),
-    Xor(String, String),
+    Gate(Gate, Vec<String>),
     Const(u64),
 }
 
+impl WireSource {
+    fn inputs(&self) -> Vec<&String> {
+        match self {
+            WireSource::Gate(_, inputs) => inputs.iter().collect(),
+            WireSource::Const(_) => vec![],
+        }
+    }
+
+    fn is(&self, gate: Gate) -> bool {
+        matches!(self, WireSource::Gate(kind, _) if *kind == gate)
+    }
+
+    /// The gate's output for the values on its inputs.
+    fn apply<I: Iterator<Item = u64>>(&self, inputs: I) -> u64 {
+        match self {
+            WireSource::Gate(gate, _) => gate.apply(inputs),
+            WireSource::Const(value) => *value,
+        }
+    }
+}
+
+#[derive(Clone)]
 struct CrossedWires {
     wires: HashMap<String, WireSource>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 enum CrossedWiresParseErr {
     NoWiresFound,
     NoGatesFound,
     BadlyFormedWire,
     BadlyFormedGate,
+    UnknownGate(String),
+    /// A gate given the wrong number of inputs, by its output wire.
+    BadInputCount(String),
+}
+
+/// Parses a gate line: `a AND b -> c`, with as many `AND b` as it has inputs, or `NOT a -> c`.
+fn parse_gate(line: &str) -> Result<(String, WireSource), CrossedWiresParseErr> {
+    let (sources, result) = line
+        .split_once(" -> ")
+        .ok_or(CrossedWiresParseErr::BadlyFormedGate)?;
+    let result = result.trim().to_string();
+    let parts: Vec<&str> = sources.split_ascii_whitespace().collect();
+    let (gate, inputs) = match parts.as_slice() {
+        ["NOT", input] => (Gate::Not, vec![input.to_string()]),
+        [_, op, ..] if parts.len() % 2 == 1 => {
+            if parts.iter().skip(1).step_by(2).any(|other| other != op) {
+                return Err(CrossedWiresParseErr::BadlyFormedGate);
+            }
+            let inputs = parts.iter().step_by(2).map(|input| input.to_string());
+            (op.parse()?, inputs.collect())
+        }
+        _ => return Err(CrossedWiresParseErr::BadlyFormedGate),
+    };
+    if !gate.accepts(inputs.len()) || result.is_empty() {
+        return Err(CrossedWiresParseErr::BadInputCount(result));
+    }
+    Ok((result, WireSource::Gate(gate, inputs)))
 }
 
 impl FromStr for CrossedWires {
@@ -51,27 +165,7 @@ impl FromStr for CrossedWires {
         }
 
         for line in lines {
-            let mut parts = line.split_ascii_whitespace();
-            let source0 = parts
-                .next()
-                .ok_or(CrossedWiresParseErr::BadlyFormedGate)?
-                .to_string();
-            let op = parts.next().ok_or(CrossedWiresParseErr::BadlyFormedGate)?;
-            let source1 = parts
-                .next()
-                .ok_or(CrossedWiresParseErr::BadlyFormedGate)?
-                .to_string();
-            let result = parts
-                .nth(1)
-                .ok_or(CrossedWiresParseErr::BadlyFormedGate)?
-                .to_string();
-
-            let gate = match op {
-                "AND" => WireSource::And(source0, source1),
-                "XOR" => WireSource::Xor(source0, source1),
-                "OR" => WireSource::Or(source0, source1),
-                _ => return Err(CrossedWiresParseErr::BadlyFormedGate),
-            };
+            let (result, gate) = parse_gate(line)?;
             wires.insert(result, gate);
         }
 
@@ -83,121 +177,119 @@ impl FromStr for CrossedWires {
     }
 }
 
-impl<'a> CrossedWires {
-    fn value(wires: &HashMap<String, WireSource>, wire: &String) -> Option<u64> {
-        match wires.get(wire) {
-            None => None,
-            Some(WireSource::Const(x)) => Some(*x),
-            Some(WireSource::And(l, r)) => CrossedWires::value(wires, l)
-                .and_then(|l| CrossedWires::value(wires, r).map(|r| l & r)),
-            Some(WireSource::Xor(l, r)) => CrossedWires::value(wires, l)
-                .and_then(|l| CrossedWires::value(wires, r).map(|r| l ^ r)),
-            Some(WireSource::Or(l, r)) => CrossedWires::value(wires, l)
-                .and_then(|l| CrossedWires::value(wires, r).map(|r| l | r)),
-        }
+impl CrossedWires {
+    fn part_a(&self) -> Result<u64, EvalErr> {
+        let mut evaluator = Evaluator::new(self)?;
+        evaluator.run();
+        let bits = evaluator.bits('z');
+        Ok(bits.iter().rev().fold(0, |acc, &bit| (acc << 1) | bit as u64))
+    }
+
+    fn part_b(&self) -> Option<String> {
+        let swaps = adder::repair(self).swaps?;
+        Some(swaps.iter().flat_map(|(l, r)| [l, r]).sorted().join(","))
     }
 
-    fn convert_starting_with(wires: &HashMap<String, WireSource>, start: char) -> Option<u64> {
-        wires
+    fn bus_width(&self, prefix: char) -> usize {
+        self.bus(prefix).len()
+    }
+
+    /// The bit position of a bus wire such as `x07`.
+    fn bus_bit(wire: &str, prefix: char) -> Option<u64> {
+        wire.strip_prefix(prefix)?.parse().ok()
+    }
+
+    /// The wires of a bus, lowest bit first.
+    fn bus(&self, prefix: char) -> Vec<&String> {
+        self.wires
             .keys()
-            .filter(|key| key.starts_with(start))
+            .filter_map(|wire| Some((CrossedWires::bus_bit(wire, prefix)?, wire)))
             .sorted()
-            .rev()
-            .try_fold(0, |acc, gate| {
-                CrossedWires::value(wires, gate).map(|val| (acc << 1) | val)
-            })
-    }
-
-    fn part_a(&self) -> Option<u64> {
-        CrossedWires::convert_starting_with(&self.wires, 'z')
-    }
-
-    fn write_wire_dot_file(wires: &HashMap<String, WireSource>, filename: &str) -> Result<(), std::io::Error> {
-        let f = File::create(filename)?;
-        let mut out = BufWriter::new(f);
-        writeln!(out, "digraph {{")?;
-        writeln!(out, "  rankdir=\"LR\";")?;
-        writeln!(out, "  node [style=filled];")?;
-
-        for (wire, source) in wires.iter() {
-            match source {
-                // Ignore consts
-                WireSource::Const(_) => (),
-                WireSource::And(left, right) => {
-                    writeln!(out, "  {wire} -> {left};")?;
-                    writeln!(out, "  {wire} -> {right};")?;
-                    writeln!(out, "  {wire} [label=\"{wire} (AND)\"];")?;
-                    writeln!(out, "  {wire} [color=\"red\"];")?;
-                }
-                WireSource::Or(left, right) => {
-                    writeln!(out, "  {wire} -> {left};")?;
-                    writeln!(out, "  {wire} -> {right};")?;
-                    writeln!(out, "  {wire} [label=\"{wire} (OR)\"];")?;
-                    writeln!(out, "  {wire} [color=\"blue\"];")?;
-                }
-                WireSource::Xor(left, right) => {
-                    writeln!(out, "  {wire} -> {left};")?;
-                    writeln!(out, "  {wire} -> {right};")?;
-                    writeln!(out, "  {wire} [label=\"{wire} (XOR)\"];")?;
-                    writeln!(out, "  {wire} [color=\"green\"];")?;
-                }
+            .map(|(_, wire)| wire)
+            .collect()
+    }
+
+    /// Exchanges the gates driving each pair of wires.
+    fn swapped(&self, pairs: &[(String, String)]) -> CrossedWires {
+        let mut wires = self.wires.clone();
+        for (left, right) in pairs {
+            if let (Some(l), Some(r)) = (wires.remove(left), wires.remove(right)) {
+                wires.insert(left.to_string(), r);
+                wires.insert(right.to_string(), l);
             }
         }
-        writeln!(out, "}}")?;
-
-        Ok(())
-    }
-
-    // https://www.reddit.com/r/adventofcode/comments/1hl698z/comment/m3v5dfv
-    fn visualize(&self) -> Result<String, std::io::Error> {
-        CrossedWires::write_wire_dot_file(&self.wires, "day24.dot")?;
-
-        // Swap our suspect wires and write again
-        let mut swapped_wires = vec![];
-        let mut swap = |left: &str, right: &str, wires: &mut HashMap<String, WireSource>| {
-            let (left_val, right_val) = match (wires.get(left), wires.get(right)) {
-                (Some(left_val), Some(right_val)) => (left_val.clone(), right_val.clone()),
-                _ => return (),
-            };
-            wires.insert(left.to_string(), right_val);
-            wires.insert(right.to_string(), left_val);
-            swapped_wires.push(left.to_string());
-            swapped_wires.push(right.to_string());
-        };
-        let mut wires: HashMap<String, WireSource> = self.wires.clone();
-        swap("ggk", "rhv", &mut wires);
-        swap("z20", "hhh", &mut wires);
-        swap("z15", "htp", &mut wires);
-        swap("z05", "dkr", &mut wires);
-
-        CrossedWires::write_wire_dot_file(&wires, "day24_swapped.dot")?;
-
-        Ok(swapped_wires.iter().sorted().join(","))
+        CrossedWires { wires }
     }
 }
 
 fn main() {
-    let puzzle = include_str!("../puzzle/input.txt");
-    let wires = CrossedWires::from_str(puzzle).expect("Error parsing puzzle");
-    println!("Part A: {:?}", wires.part_a());
-    println!("Part B: {:?}", wires.visualize());
+    let runner = Runner::from_args();
+    let puzzle = runner.read_input(util::input_path!()).expect("Unable to read puzzle input");
+    let wires = match puzzle.trim_start().starts_with('{') {
+        true => netlist::from_json(&puzzle).expect("Error reading netlist"),
+        false => CrossedWires::from_str(&puzzle).expect("Error parsing puzzle"),
+    };
+    if let Some(format) = runner.export() {
+        print!("{}", netlist::export(&wires, format).expect("Unable to export circuit"));
+        return;
+    }
+    runner.solve("A", || wires.part_a());
+    runner.solve("B", || wires.part_b());
+    runner.details("B", || {
+        let check = match Simulator::new(&wires).map(|mut sim| sim.check(|x, y| x + y)) {
+            Ok(Ok(())) => "adds correctly".to_string(),
+            Ok(Err(mismatch)) => mismatch.to_string(),
+            Err(err) => format!("{:?}", err),
+        };
+        let faults = adder::repair(&wires).faults.into_iter();
+        let faults = faults.map(|fault| (fault.wire, fault.rule.describe().to_string()));
+        [("as given".to_string(), check)]
+            .into_iter()
+            .chain(faults)
+            .collect::<Vec<_>>()
+    });
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use util::{
+        example::{self, ExampleErr},
+        output::Answer,
+    };
 
     #[test]
-    fn test_test_small_txt() {
-        let puzzle = include_str!("../puzzle/test_small.txt");
-        let wires = CrossedWires::from_str(puzzle).unwrap();
-        assert_eq!(Some(4), wires.part_a());
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            let wires = CrossedWires::from_str(&example.puzzle).unwrap();
+            match part {
+                "A" => Ok(wires.part_a().render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
     }
 
     #[test]
-    fn test_test_large_txt() {
-        let puzzle = include_str!("../puzzle/test_large.txt");
-        let wires = CrossedWires::from_str(puzzle).unwrap();
-        assert_eq!(Some(2024), wires.part_a());
+    fn test_parse_gates() {
+        let (output, source) = parse_gate("a AND b AND c -> d").unwrap();
+        let inputs = ["a", "b", "c"].map(|input| input.to_string()).to_vec();
+        assert_eq!((output.as_str(), source), ("d", WireSource::Gate(Gate::And, inputs)));
+        assert_eq!(
+            parse_gate("NOT a -> b").unwrap().1,
+            WireSource::Gate(Gate::Not, vec!["a".to_string()])
+        );
+        assert_eq!(
+            parse_gate("a AND b OR c -> d"),
+            Err(CrossedWiresParseErr::BadlyFormedGate)
+        );
+        assert_eq!(
+            parse_gate("a MUX b -> d"),
+            Err(CrossedWiresParseErr::UnknownGate("MUX".to_string()))
+        );
+        assert_eq!(
+            parse_gate("a NOT b -> d"),
+            Err(CrossedWiresParseErr::BadInputCount("d".to_string()))
+        );
+        assert_eq!(parse_gate("a AND b"), Err(CrossedWiresParseErr::BadlyFormedGate));
     }
 }
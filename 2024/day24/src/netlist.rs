@@ -0,0 +1,270 @@
+//! Writes a circuit out as structural Verilog or as a JSON netlist, and reads the JSON back, so
+//! a circuit can be run through other simulators.
+//!
+//! The JSON has an `inputs` object mapping each constant wire to its value, and a `gates` array
+//! of `{"output": "z00", "gate": "AND", "inputs": ["x00", "y00"]}` objects, with gates named as
+//! the puzzle names them. In the Verilog the constant wires become input ports and the `z` bus the
+//! output ports, and each gate is one primitive instance.
+
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use serde_json::{json, Value};
+
+use crate::{CrossedWires, CrossedWiresParseErr, Gate, WireSource};
+
+/// The reserved words of IEEE 1364-2005 (Verilog-2005), which have to be escaped to be used as
+/// wires.
+const KEYWORDS: &str = "\
+    always and assign automatic begin buf bufif0 bufif1 case casex casez cell cmos config \
+    deassign default defparam design disable edge else end endcase endconfig endfunction \
+    endgenerate endmodule endprimitive endspecify endtable endtask event for force forever \
+    fork function generate genvar highz0 highz1 if ifnone incdir include initial inout input \
+    instance integer join large liblist library localparam macromodule medium module nand \
+    negedge nmos nor noshowcancelled not notif0 notif1 or output parameter pmos posedge \
+    primitive pull0 pull1 pulldown pullup pulsestyle_ondetect pulsestyle_onevent rcmos real \
+    realtime reg release repeat rnmos rpmos rtran rtranif0 rtranif1 scalared showcancelled \
+    signed small specify specparam strong0 strong1 supply0 supply1 table task time tran \
+    tranif0 tranif1 tri tri0 tri1 triand trior trireg unsigned use uwire vectored wait wand \
+    weak0 weak1 while wire wor xnor xor";
+
+#[derive(Debug, PartialEq)]
+pub enum NetlistErr {
+    UnknownFormat(String),
+    BadJson(String),
+    Missing(&'static str),
+    /// A wire given as an input and as a gate output, or as the output of two gates.
+    DuplicateWire(String),
+    BadGate(CrossedWiresParseErr),
+}
+
+/// The circuit in `format`, either `verilog` or `json`.
+pub fn export(circuit: &CrossedWires, format: &str) -> Result<String, NetlistErr> {
+    match format {
+        "verilog" => Ok(to_verilog(circuit)),
+        "json" => Ok(to_json(circuit)),
+        _ => Err(NetlistErr::UnknownFormat(format.to_string())),
+    }
+}
+
+/// A wire name as a Verilog identifier, escaped where it is not a plain one.
+fn identifier(name: &str) -> String {
+    let mut chars = name.chars();
+    let plain = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+        && !KEYWORDS.split_whitespace().any(|keyword| keyword == name);
+    match plain {
+        true => name.to_string(),
+        false => format!("\\{} ", name),
+    }
+}
+
+fn sorted_wires(circuit: &CrossedWires) -> impl Iterator<Item = (&String, &WireSource)> {
+    circuit.wires.iter().sorted_by_key(|(wire, _)| *wire)
+}
+
+pub fn to_verilog(circuit: &CrossedWires) -> String {
+    let is_output = |wire: &str, source: &WireSource| {
+        CrossedWires::bus_bit(wire, 'z').is_some() && !matches!(source, WireSource::Const(_))
+    };
+    let mut ports = vec![];
+    let mut wires = vec![];
+    let mut gates = vec![];
+    for (wire, source) in sorted_wires(circuit) {
+        let name = identifier(wire);
+        match source {
+            WireSource::Const(_) => ports.push(format!("input {}", name)),
+            WireSource::Gate(gate, inputs) => {
+                if !is_output(wire, source) {
+                    wires.push(format!("    wire {};", name));
+                }
+                let inputs = inputs.iter().map(|input| identifier(input)).join(", ");
+                let primitive = gate.name().to_lowercase();
+                gates.push(format!("    {} ({}, {});", primitive, name, inputs));
+            }
+        }
+    }
+    for wire in circuit.bus('z') {
+        if is_output(wire, &circuit.wires[wire]) {
+            ports.push(format!("output {}", identifier(wire)));
+        }
+    }
+
+    let ports = ports.iter().map(|port| format!("    {}", port)).join(",\n");
+    let mut lines = vec![format!("module crossed_wires (\n{}\n);", ports)];
+    lines.extend(wires);
+    if lines.len() > 1 {
+        lines.push(String::new());
+    }
+    lines.extend(gates);
+    lines.push("endmodule\n".to_string());
+    lines.join("\n")
+}
+
+pub fn to_json(circuit: &CrossedWires) -> String {
+    let mut inputs = serde_json::Map::new();
+    let mut gates = vec![];
+    for (wire, source) in sorted_wires(circuit) {
+        match source {
+            WireSource::Const(value) => {
+                inputs.insert(wire.to_string(), json!(value));
+            }
+            WireSource::Gate(gate, sources) => gates.push(json!({
+                "output": wire,
+                "gate": gate.name(),
+                "inputs": sources,
+            })),
+        }
+    }
+    let netlist = json!({ "inputs": inputs, "gates": gates });
+    serde_json::to_string_pretty(&netlist).unwrap_or_default()
+}
+
+fn string(value: &Value, key: &'static str) -> Result<String, NetlistErr> {
+    let field = value.get(key).ok_or(NetlistErr::Missing(key))?;
+    let string = field
+        .as_str()
+        .ok_or(NetlistErr::BadJson(field.to_string()))?;
+    Ok(string.to_string())
+}
+
+pub fn from_json(json: &str) -> Result<CrossedWires, NetlistErr> {
+    let netlist: Value =
+        serde_json::from_str(json).map_err(|e| NetlistErr::BadJson(e.to_string()))?;
+    let inputs = netlist["inputs"]
+        .as_object()
+        .ok_or(NetlistErr::Missing("inputs"))?;
+    let gates = netlist["gates"]
+        .as_array()
+        .ok_or(NetlistErr::Missing("gates"))?;
+
+    let mut wires = HashMap::new();
+    for (wire, value) in inputs {
+        let value = value
+            .as_u64()
+            .ok_or(NetlistErr::BadJson(value.to_string()))?;
+        wires.insert(wire.to_string(), WireSource::Const(value));
+    }
+    for gate in gates {
+        let output = string(gate, "output")?;
+        let kind: Gate = string(gate, "gate")?.parse().map_err(NetlistErr::BadGate)?;
+        let sources = gate["inputs"]
+            .as_array()
+            .ok_or(NetlistErr::Missing("inputs"))?;
+        let sources = sources
+            .iter()
+            .map(|source| source.as_str().map(|s| s.to_string()))
+            .collect::<Option<Vec<String>>>()
+            .ok_or(NetlistErr::BadJson(gate["inputs"].to_string()))?;
+        if !kind.accepts(sources.len()) {
+            let err = CrossedWiresParseErr::BadInputCount(output);
+            return Err(NetlistErr::BadGate(err));
+        }
+        if wires.contains_key(&output) {
+            return Err(NetlistErr::DuplicateWire(output));
+        }
+        wires.insert(output, WireSource::Gate(kind, sources));
+    }
+    Ok(CrossedWires { wires })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const PUZZLE: &str = "x00: 1\ny00: 0\n\nx00 XOR y00 -> and\nNOT and -> z01\n\
+                          x00 NOR y00 NOR and -> z00";
+
+    #[test]
+    fn test_verilog() {
+        let circuit = CrossedWires::from_str(PUZZLE).unwrap();
+        let expected = [
+            "module crossed_wires (",
+            "    input x00,",
+            "    input y00,",
+            "    output z00,",
+            "    output z01",
+            ");",
+            "    wire \\and ;",
+            "",
+            "    xor (\\and , x00, y00);",
+            "    nor (z00, x00, y00, \\and );",
+            "    not (z01, \\and );",
+            "endmodule",
+            "",
+        ];
+        assert_eq!(export(&circuit, "verilog").unwrap(), expected.join("\n"));
+    }
+
+    #[test]
+    fn test_identifiers() {
+        let circuit = CrossedWires::from_str("tri: 1\nx00: 0\n\ntri OR x00 -> wand\n").unwrap();
+        let verilog = export(&circuit, "verilog").unwrap();
+        assert!(verilog.contains("    input \\tri ,\n"));
+        assert!(verilog.contains("    or (\\wand , \\tri , x00);"));
+        assert_eq!(identifier("tri0"), "\\tri0 ");
+        assert_eq!(identifier("pulsestyle_ondetect"), "\\pulsestyle_ondetect ");
+        assert_eq!(identifier("trio"), "trio");
+        assert_eq!(identifier("z00"), "z00");
+        assert_eq!(identifier("1x"), "\\1x ");
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let circuit = CrossedWires::from_str(PUZZLE).unwrap();
+        let json = export(&circuit, "json").unwrap();
+        assert!(json.contains("\"gate\": \"NOR\""));
+        let read = from_json(&json).unwrap();
+        assert_eq!(read.wires, circuit.wires);
+        assert_eq!(read.part_a().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_json_errors() {
+        let read = |output: &str, gate: &str, inputs: &str| {
+            let gate = format!(
+                r#"{{"output": "{}", "gate": "{}", "inputs": {}}}"#,
+                output, gate, inputs
+            );
+            from_json(&format!(
+                r#"{{"inputs": {{"x00": 1}}, "gates": [{}]}}"#,
+                gate
+            ))
+            .err()
+        };
+        assert_eq!(read("z00", "AND", r#"["x00", "x00", "x00"]"#), None);
+        assert_eq!(
+            read("z00", "MUX", r#"["x00", "x00"]"#),
+            Some(NetlistErr::BadGate(CrossedWiresParseErr::UnknownGate(
+                "MUX".to_string()
+            )))
+        );
+        assert_eq!(
+            read("z00", "NOT", r#"["x00", "x00"]"#),
+            Some(NetlistErr::BadGate(CrossedWiresParseErr::BadInputCount(
+                "z00".to_string()
+            )))
+        );
+        assert_eq!(
+            read("z00", "OR", "[1, 2]"),
+            Some(NetlistErr::BadJson("[1,2]".to_string()))
+        );
+        assert_eq!(
+            read("x00", "OR", r#"["x00", "x00"]"#),
+            Some(NetlistErr::DuplicateWire("x00".to_string()))
+        );
+        assert_eq!(
+            from_json(r#"{"inputs": {}}"#).err(),
+            Some(NetlistErr::Missing("gates"))
+        );
+
+        let empty = from_json(r#"{"inputs": {}, "gates": []}"#).unwrap();
+        assert_eq!(
+            export(&empty, "vhdl"),
+            Err(NetlistErr::UnknownFormat("vhdl".to_string()))
+        );
+    }
+}
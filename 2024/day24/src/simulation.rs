@@ -0,0 +1,230 @@
+//! Runs a circuit on chosen `x` and `y` operands of any width, and checks it against a reference
+//! operation over every single-bit case, the carry chains and random operands.
+
+use std::fmt;
+
+use num_bigint::BigUint;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    evaluator::{EvalErr, Evaluator},
+    CrossedWires,
+};
+
+const RANDOM_TRIALS: usize = 200;
+const SEED: u64 = 24;
+
+/// An input the circuit gets wrong, shrunk until clearing any one operand bit makes it right.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub x: BigUint,
+    pub y: BigUint,
+    pub expected: BigUint,
+    pub actual: BigUint,
+    /// The lowest `z` bit that differs.
+    pub bit: usize,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "x={} y={}: expected {}, got {} (z{:02} wrong)",
+            self.x, self.y, self.expected, self.actual, self.bit
+        )
+    }
+}
+
+pub struct Simulator {
+    evaluator: Evaluator,
+    outputs: usize,
+    width: usize,
+}
+
+impl Simulator {
+    pub fn new(circuit: &CrossedWires) -> Result<Simulator, EvalErr> {
+        Ok(Simulator {
+            evaluator: Evaluator::new(circuit)?,
+            outputs: circuit.bus_width('z'),
+            width: circuit.bus_width('x').max(circuit.bus_width('y')),
+        })
+    }
+
+    /// Every `z` bit for the operands, lowest first.
+    pub fn run<X: Into<BigUint>, Y: Into<BigUint>>(&mut self, x: X, y: Y) -> Vec<bool> {
+        self.evaluator.set_bus('x', &x.into());
+        self.evaluator.set_bus('y', &y.into());
+        self.evaluator.run();
+        self.evaluator.bits('z')
+    }
+
+    /// Compares one input against the reference, keeping only as many bits as `z` has.
+    fn mismatch<F>(&mut self, reference: &F, x: &BigUint, y: &BigUint) -> Option<Mismatch>
+    where
+        F: Fn(&BigUint, &BigUint) -> BigUint,
+    {
+        let full = reference(x, y);
+        let mut expected = BigUint::ZERO;
+        for bit in 0..self.outputs as u64 {
+            expected.set_bit(bit, full.bit(bit));
+        }
+        let bits = self.run(x.clone(), y.clone());
+        let bit = (0..bits.len()).find(|&bit| bits[bit] != expected.bit(bit as u64))?;
+        Some(Mismatch {
+            x: x.clone(),
+            y: y.clone(),
+            expected,
+            actual: to_number(&bits),
+            bit,
+        })
+    }
+
+    /// Clears operand bits from the top down for as long as the input still fails.
+    fn shrink<F>(&mut self, reference: &F, mut mismatch: Mismatch) -> Mismatch
+    where
+        F: Fn(&BigUint, &BigUint) -> BigUint,
+    {
+        let mut shrunk = true;
+        while shrunk {
+            shrunk = false;
+            for operand in 0..2 {
+                for bit in (0..self.width as u64).rev() {
+                    let (mut x, mut y) = (mismatch.x.clone(), mismatch.y.clone());
+                    let value = if operand == 0 { &mut x } else { &mut y };
+                    if !value.bit(bit) {
+                        continue;
+                    }
+                    value.set_bit(bit, false);
+                    if let Some(smaller) = self.mismatch(reference, &x, &y) {
+                        mismatch = smaller;
+                        shrunk = true;
+                    }
+                }
+            }
+        }
+        mismatch
+    }
+
+    /// Checks the circuit against `reference` on the simplest inputs first, so the mismatch
+    /// reported is as small as the inputs that show it.
+    pub fn check<F>(&mut self, reference: F) -> Result<(), Mismatch>
+    where
+        F: Fn(&BigUint, &BigUint) -> BigUint,
+    {
+        let width = self.width as u64;
+        let one = |bit: u64| {
+            let mut value = BigUint::ZERO;
+            value.set_bit(bit, true);
+            value
+        };
+        let ones = (BigUint::from(1u8) << width) - 1u8;
+        let zero = BigUint::ZERO;
+
+        let mut inputs = vec![(zero.clone(), zero.clone())];
+        for bit in 0..width {
+            inputs.push((one(bit), zero.clone()));
+            inputs.push((zero.clone(), one(bit)));
+            inputs.push((one(bit), one(bit)));
+        }
+        inputs.push((ones.clone(), BigUint::from(1u8)));
+        inputs.push((ones.clone(), ones.clone()));
+
+        let mut rng = StdRng::seed_from_u64(SEED);
+        let mut random = || {
+            let mut value = BigUint::ZERO;
+            (0..width).for_each(|bit| value.set_bit(bit, rng.gen()));
+            value
+        };
+        inputs.extend((0..RANDOM_TRIALS).map(|_| (random(), random())));
+
+        for (x, y) in inputs {
+            if let Some(mismatch) = self.mismatch(&reference, &x, &y) {
+                return Err(self.shrink(&reference, mismatch));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The number the bits make, lowest bit first.
+pub fn to_number(bits: &[bool]) -> BigUint {
+    let mut number = BigUint::ZERO;
+    for (bit, value) in bits.iter().enumerate() {
+        number.set_bit(bit as u64, *value);
+    }
+    number
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    /// `z` is `x` combined bitwise with `y` by `op`, except where `faults` swaps in another.
+    fn bitwise(width: usize, op: &str, faults: &[(usize, &str)]) -> CrossedWires {
+        let mut lines = vec![];
+        for bit in 0..width {
+            lines.push(format!("x{:02}: 0", bit));
+            lines.push(format!("y{:02}: 0", bit));
+        }
+        lines.push(String::new());
+        for bit in 0..width {
+            let op = faults
+                .iter()
+                .find(|(at, _)| *at == bit)
+                .map_or(op, |(_, op)| op);
+            lines.push(format!("x{0:02} {1} y{0:02} -> z{0:02}", bit, op));
+        }
+        CrossedWires::from_str(&lines.join("\n")).unwrap()
+    }
+
+    #[test]
+    fn test_run() {
+        let mut simulator = Simulator::new(&bitwise(4, "AND", &[])).unwrap();
+        let bits = simulator.run(0b1101u8, 0b0111u8);
+        assert_eq!(bits, [true, false, true, false]);
+        assert_eq!(to_number(&simulator.run(0b11111u8, 0u8)), BigUint::ZERO);
+
+        let mut simulator = Simulator::new(&bitwise(80, "XOR", &[])).unwrap();
+        let x = BigUint::from(1u128 << 79 | 5);
+        let xor = to_number(&simulator.run(x, 3u8));
+        assert_eq!(xor, BigUint::from(1u128 << 79 | 6));
+        assert_eq!(simulator.check(|x, y| x ^ y), Ok(()));
+    }
+
+    #[test]
+    fn test_check() {
+        let mut simulator = Simulator::new(&bitwise(6, "AND", &[])).unwrap();
+        assert_eq!(simulator.check(|x, y| x & y), Ok(()));
+        let mismatch = simulator.check(|x, y| x + y).unwrap_err();
+        assert_eq!(
+            (mismatch.x, mismatch.y, mismatch.bit),
+            (1u8.into(), 0u8.into(), 0)
+        );
+
+        let mut simulator = Simulator::new(&bitwise(6, "AND", &[(2, "OR")])).unwrap();
+        let mismatch = simulator.check(|x, y| x & y).unwrap_err();
+        assert_eq!(
+            mismatch.to_string(),
+            "x=4 y=0: expected 0, got 4 (z02 wrong)"
+        );
+    }
+
+    #[test]
+    fn test_shrink() {
+        let mut simulator = Simulator::new(&bitwise(6, "OR", &[(3, "XOR")])).unwrap();
+        let reference = |x: &BigUint, y: &BigUint| x | y;
+        let found = simulator
+            .mismatch(
+                &reference,
+                &BigUint::from(0b111111u8),
+                &BigUint::from(0b101010u8),
+            )
+            .unwrap();
+        let shrunk = simulator.shrink(&reference, found);
+        assert_eq!(
+            (shrunk.x, shrunk.y, shrunk.bit),
+            (8u8.into(), 8u8.into(), 3)
+        );
+    }
+}
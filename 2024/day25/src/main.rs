@@ -1,6 +1,7 @@
 use std::str::FromStr;
 
 use thiserror::Error;
+use util::runner::Runner;
 
 #[derive(Debug)]
 struct CodeChronicle {
@@ -91,9 +92,10 @@ impl CodeChronicle {
 }
 
 fn main() -> Result<(), CodeChronicleParseErr> {
-    let puzzle = include_str!("../puzzle/test.txt");
-    let chronicle = CodeChronicle::from_str(puzzle);
-    println!("Part A: {}", chronicle?.part_a());
+    let runner = Runner::from_args();
+    let puzzle = runner.read_input(util::input_path!()).expect("Unable to read puzzle input");
+    let chronicle = CodeChronicle::from_str(&puzzle)?;
+    runner.solve("A", || chronicle.part_a());
 
     Ok(())
 }
@@ -101,11 +103,19 @@ fn main() -> Result<(), CodeChronicleParseErr> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use util::{
+        example::{self, ExampleErr},
+        output::Answer,
+    };
 
     #[test]
-    fn test_test_txt() {
-        let puzzle = include_str!("../puzzle/test.txt");
-        let chronicle = CodeChronicle::from_str(puzzle).unwrap();
-        assert_eq!(chronicle.part_a(), 3);
+    fn test_examples() {
+        example::check_all(env!("CARGO_MANIFEST_DIR"), |example, part| {
+            let chronicle = CodeChronicle::from_str(&example.puzzle).unwrap();
+            match part {
+                "A" => Ok(chronicle.part_a().render()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        });
     }
 }
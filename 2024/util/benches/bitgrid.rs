@@ -0,0 +1,84 @@
+use std::collections::HashSet;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use util::{
+    bitgrid::{BitGrid, DirectedBitGrid},
+    direction::Direction,
+    point::Point,
+};
+
+const SIZE: usize = 130;
+
+// Deterministic scatter of obstacles, roughly one cell in eleven.
+fn obstacles() -> Vec<Point> {
+    let mut seed: u32 = 0x2024;
+    let mut points = vec![];
+    for row in 0..SIZE {
+        for col in 0..SIZE {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            if (seed >> 16).is_multiple_of(11) {
+                points.push(Point::from((row, col)));
+            }
+        }
+    }
+    points
+}
+
+fn in_bounds(point: &Point) -> bool {
+    point.0 >= 0 && point.1 >= 0 && point.0 < SIZE as i32 && point.1 < SIZE as i32
+}
+
+// The day06 guard walk: turn right at obstacles, stop on leaving the grid or repeating a state.
+fn walk_hashset(obstacles: &HashSet<Point>, visited: &mut HashSet<(Point, Direction)>) -> usize {
+    visited.clear();
+    let (mut pos, mut dir) = (Point(SIZE as i32 / 2, SIZE as i32 / 2), Direction::Up);
+    while in_bounds(&pos) && visited.insert((pos, dir)) {
+        let next = pos.add(&dir.to_point());
+        if obstacles.contains(&next) {
+            dir = dir.right90();
+        } else {
+            pos = next;
+        }
+    }
+    visited.len()
+}
+
+fn walk_bitgrid(obstacles: &BitGrid, visited: &mut DirectedBitGrid) -> usize {
+    visited.clear();
+    let (mut pos, mut dir) = (Point(SIZE as i32 / 2, SIZE as i32 / 2), Direction::Up);
+    while obstacles.in_bounds(&pos) && visited.insert(&pos, &dir) {
+        let next = pos.add(&dir.to_point());
+        if obstacles.contains(&next) {
+            dir = dir.right90();
+        } else {
+            pos = next;
+        }
+    }
+    visited.len()
+}
+
+fn guard_walk(c: &mut Criterion) {
+    let points = obstacles();
+    let hash_obstacles: HashSet<Point> = points.iter().copied().collect();
+    let mut bit_obstacles = BitGrid::new(SIZE, SIZE);
+    points.iter().for_each(|p| _ = bit_obstacles.insert(p));
+
+    let mut hash_visited = HashSet::new();
+    let mut bit_visited = DirectedBitGrid::new(SIZE, SIZE);
+    assert_eq!(
+        walk_hashset(&hash_obstacles, &mut hash_visited),
+        walk_bitgrid(&bit_obstacles, &mut bit_visited)
+    );
+
+    let mut group = c.benchmark_group("guard_walk");
+    group.bench_function("hashset", |b| {
+        b.iter(|| walk_hashset(&hash_obstacles, &mut hash_visited))
+    });
+    group.bench_function("bitgrid", |b| {
+        b.iter(|| walk_bitgrid(&bit_obstacles, &mut bit_visited))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, guard_walk);
+criterion_main!(benches);
@@ -0,0 +1,220 @@
+use crate::{direction::Direction, point::Point};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A fixed-size set of grid points packed one bit per cell. Points are `(row, col)` as everywhere
+/// else; anything outside the grid is never contained and cannot be inserted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitGrid {
+    height: usize,
+    width: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(height: usize, width: usize) -> BitGrid {
+        BitGrid {
+            height,
+            width,
+            words: vec![0; (height * width).div_ceil(WORD_BITS)],
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn in_bounds(&self, point: &Point) -> bool {
+        point.0 >= 0
+            && point.1 >= 0
+            && (point.0 as usize) < self.height
+            && (point.1 as usize) < self.width
+    }
+
+    fn bit(&self, point: &Point) -> Option<(usize, u64)> {
+        if !self.in_bounds(point) {
+            return None;
+        }
+        let idx = point.0 as usize * self.width + point.1 as usize;
+        Some((idx / WORD_BITS, 1 << (idx % WORD_BITS)))
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        self.bit(point)
+            .is_some_and(|(word, mask)| self.words[word] & mask != 0)
+    }
+
+    /// Sets the cell, returning true if it was not already set. Out of bounds points are ignored
+    /// and return false.
+    pub fn insert(&mut self, point: &Point) -> bool {
+        let Some((word, mask)) = self.bit(point) else {
+            return false;
+        };
+        let was_set = self.words[word] & mask != 0;
+        self.words[word] |= mask;
+        !was_set
+    }
+
+    /// Clears the cell, returning true if it was set.
+    pub fn remove(&mut self, point: &Point) -> bool {
+        let Some((word, mask)) = self.bit(point) else {
+            return false;
+        };
+        let was_set = self.words[word] & mask != 0;
+        self.words[word] &= !mask;
+        was_set
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    /// Every set point in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = Point> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(|(word_idx, &word)| {
+                let mut remaining = word;
+                std::iter::from_fn(move || {
+                    if remaining == 0 {
+                        return None;
+                    }
+                    let bit = remaining.trailing_zeros() as usize;
+                    remaining &= remaining - 1;
+                    Some(word_idx * WORD_BITS + bit)
+                })
+            })
+            .map(|idx| Point::from((idx / self.width, idx % self.width)))
+    }
+}
+
+/// A `BitGrid` with one layer per `Direction`, for recording states such as "was here facing up".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectedBitGrid {
+    layers: BitGrid,
+}
+
+impl DirectedBitGrid {
+    const LAYERS: usize = 8;
+
+    pub fn new(height: usize, width: usize) -> DirectedBitGrid {
+        DirectedBitGrid {
+            layers: BitGrid::new(height, width * DirectedBitGrid::LAYERS),
+        }
+    }
+
+    fn layer(direction: &Direction) -> i32 {
+        match direction {
+            Direction::Up => 0,
+            Direction::UpRight => 1,
+            Direction::Right => 2,
+            Direction::DownRight => 3,
+            Direction::Down => 4,
+            Direction::DownLeft => 5,
+            Direction::Left => 6,
+            Direction::UpLeft => 7,
+        }
+    }
+
+    fn flatten(&self, point: &Point, direction: &Direction) -> Option<Point> {
+        let width = self.layers.width / DirectedBitGrid::LAYERS;
+        if point.1 < 0 || point.1 as usize >= width {
+            return None;
+        }
+        let col = point.1 * DirectedBitGrid::LAYERS as i32 + DirectedBitGrid::layer(direction);
+        Some(Point(point.0, col))
+    }
+
+    pub fn contains(&self, point: &Point, direction: &Direction) -> bool {
+        self.flatten(point, direction)
+            .is_some_and(|p| self.layers.contains(&p))
+    }
+
+    pub fn insert(&mut self, point: &Point, direction: &Direction) -> bool {
+        self.flatten(point, direction)
+            .is_some_and(|p| self.layers.insert(&p))
+    }
+
+    pub fn remove(&mut self, point: &Point, direction: &Direction) -> bool {
+        self.flatten(point, direction)
+            .is_some_and(|p| self.layers.remove(&p))
+    }
+
+    pub fn clear(&mut self) {
+        self.layers.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_contains_remove() {
+        let mut grid = BitGrid::new(3, 70);
+        assert!(grid.insert(&Point(2, 69)));
+        assert!(!grid.insert(&Point(2, 69)));
+        assert!(grid.contains(&Point(2, 69)));
+        assert!(!grid.contains(&Point(1, 69)));
+        assert!(grid.remove(&Point(2, 69)));
+        assert!(!grid.remove(&Point(2, 69)));
+        assert!(grid.is_empty());
+    }
+
+    #[test]
+    fn test_out_of_bounds() {
+        let mut grid = BitGrid::new(2, 2);
+        assert!(!grid.insert(&Point(-1, 0)));
+        assert!(!grid.insert(&Point(0, 2)));
+        assert!(!grid.contains(&Point(2, 0)));
+        assert_eq!(grid.len(), 0);
+    }
+
+    #[test]
+    fn test_iter_and_clear() {
+        let mut grid = BitGrid::new(10, 13);
+        let points = vec![Point(0, 0), Point(4, 12), Point(5, 0), Point(9, 12)];
+        points.iter().for_each(|p| _ = grid.insert(p));
+        assert_eq!(grid.len(), 4);
+        assert_eq!(grid.iter().collect::<Vec<_>>(), points);
+        grid.clear();
+        assert!(grid.is_empty());
+        assert_eq!(grid.iter().count(), 0);
+    }
+
+    #[test]
+    fn test_directed() {
+        let mut grid = DirectedBitGrid::new(4, 4);
+        assert!(grid.insert(&Point(1, 3), &Direction::Up));
+        assert!(grid.contains(&Point(1, 3), &Direction::Up));
+        assert!(!grid.contains(&Point(1, 3), &Direction::Down));
+        assert!(!grid.contains(&Point(1, 2), &Direction::Up));
+        assert!(!grid.insert(&Point(1, 4), &Direction::Up));
+        assert!(grid.insert(&Point(1, 3), &Direction::UpLeft));
+        assert_eq!(grid.len(), 2);
+        assert!(grid.remove(&Point(1, 3), &Direction::Up));
+        grid.clear();
+        assert!(grid.is_empty());
+    }
+}
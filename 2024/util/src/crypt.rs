@@ -0,0 +1,202 @@
+//! Puzzle inputs stored encrypted, so they can be committed without publishing them.
+//!
+//! An encrypted input sits beside where the plain one would be, as `input.txt.enc`, and is only
+//! ever decrypted in memory. The key is a passphrase taken from `AOC_INPUT_KEY`, or failing that
+//! from the first line of `.input-key` in the year directory. Each file gets its own
+//! ChaCha20-Poly1305 key, derived from the passphrase and a random salt with PBKDF2-HMAC-SHA256,
+//! and is a short header, the round count, the salt, a random nonce and the ciphertext.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    ChaCha20Poly1305, Nonce,
+};
+use sha2::Sha256;
+
+pub const KEY_VAR: &str = "AOC_INPUT_KEY";
+pub const KEY_FILE: &str = ".input-key";
+pub const EXTENSION: &str = "enc";
+const HEADER: &[u8] = b"aoc-enc2";
+const ROUNDS_LEN: usize = 4;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+#[derive(Debug, PartialEq)]
+pub enum CryptErr {
+    NotEncrypted,
+    WrongKey,
+    /// A header asking for more PBKDF2 rounds than `Key::MAX_ROUNDS`, which would take hours to
+    /// derive before the key could even be checked.
+    TooManyRounds(u32),
+}
+
+pub struct Key {
+    passphrase: String,
+    rounds: u32,
+}
+
+impl Key {
+    /// PBKDF2 rounds for newly encrypted files. Decrypting uses whatever the file was written with.
+    pub const DEFAULT_ROUNDS: u32 = 600_000;
+    pub const MAX_ROUNDS: u32 = 10 * Key::DEFAULT_ROUNDS;
+
+    pub fn from_passphrase(passphrase: &str) -> Key {
+        Key {
+            passphrase: passphrase.trim().to_string(),
+            rounds: Key::DEFAULT_ROUNDS,
+        }
+    }
+
+    pub fn with_rounds(mut self, rounds: u32) -> Key {
+        self.rounds = rounds.clamp(1, Key::MAX_ROUNDS);
+        self
+    }
+
+    /// The key from `AOC_INPUT_KEY`, or from the key file if the variable is not set.
+    pub fn find() -> Option<Key> {
+        let passphrase = match env::var(KEY_VAR) {
+            Ok(passphrase) => passphrase,
+            Err(_) => fs::read_to_string(key_file()).ok()?,
+        };
+        let passphrase = passphrase.lines().next().unwrap_or_default().trim();
+        if passphrase.is_empty() {
+            return None;
+        }
+        Some(Key::from_passphrase(passphrase))
+    }
+
+    fn cipher(&self, salt: &[u8], rounds: u32) -> ChaCha20Poly1305 {
+        let mut key = chacha20poly1305::Key::default();
+        pbkdf2::pbkdf2_hmac::<Sha256>(self.passphrase.as_bytes(), salt, rounds, &mut key);
+        ChaCha20Poly1305::new(&key)
+    }
+
+    pub fn encrypt(&self, plain: &[u8]) -> Vec<u8> {
+        let mut salt = [0; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher(&salt, self.rounds)
+            .encrypt(&nonce, plain)
+            .expect("Encrypting into memory cannot fail");
+        let rounds = self.rounds.to_be_bytes();
+        [HEADER, &rounds, &salt, nonce.as_slice(), &ciphertext].concat()
+    }
+
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, CryptErr> {
+        let rest = data.strip_prefix(HEADER).ok_or(CryptErr::NotEncrypted)?;
+        if rest.len() < ROUNDS_LEN + SALT_LEN + NONCE_LEN {
+            return Err(CryptErr::NotEncrypted);
+        }
+        let (rounds, rest) = rest.split_at(ROUNDS_LEN);
+        let (salt, rest) = rest.split_at(SALT_LEN);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+        let rounds = u32::from_be_bytes(rounds.try_into().map_err(|_| CryptErr::NotEncrypted)?);
+        if rounds == 0 {
+            return Err(CryptErr::NotEncrypted);
+        }
+        if rounds > Key::MAX_ROUNDS {
+            return Err(CryptErr::TooManyRounds(rounds));
+        }
+        self.cipher(salt, rounds)
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| CryptErr::WrongKey)
+    }
+}
+
+/// Where the key file lives: the year directory, beside `util` and the days.
+pub fn key_file() -> PathBuf {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    manifest_dir.parent().unwrap_or(manifest_dir).join(KEY_FILE)
+}
+
+/// `input.txt` becomes `input.txt.enc`.
+pub fn encrypted_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(EXTENSION);
+    PathBuf::from(name)
+}
+
+pub fn is_encrypted(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == EXTENSION)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Few enough rounds for a debug build.
+    fn key(passphrase: &str) -> Key {
+        Key::from_passphrase(passphrase).with_rounds(1000)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let key = key("correct horse");
+        let data = key.encrypt(b"3   4\n4   3\n");
+        assert!(data.starts_with(HEADER));
+        assert_eq!(
+            data[HEADER.len()..HEADER.len() + ROUNDS_LEN],
+            1000u32.to_be_bytes()
+        );
+        assert_ne!(key.encrypt(b"3   4\n4   3\n"), data);
+        assert_eq!(key.decrypt(&data).unwrap(), b"3   4\n4   3\n");
+        let trimmed = Key::from_passphrase(" correct horse\n");
+        assert_eq!(trimmed.decrypt(&data).unwrap(), b"3   4\n4   3\n");
+    }
+
+    #[test]
+    fn test_salted() {
+        let key = key("correct horse");
+        let salt = |data: Vec<u8>| data[HEADER.len() + ROUNDS_LEN..][..SALT_LEN].to_vec();
+        assert_ne!(salt(key.encrypt(b"1\n")), salt(key.encrypt(b"1\n")));
+    }
+
+    #[test]
+    fn test_decrypt_errors() {
+        let data = key("correct horse").encrypt(b"1\n");
+        let wrong = key("battery staple");
+        assert_eq!(wrong.decrypt(&data), Err(CryptErr::WrongKey));
+        assert_eq!(wrong.decrypt(b"1\n"), Err(CryptErr::NotEncrypted));
+        assert_eq!(wrong.decrypt(HEADER), Err(CryptErr::NotEncrypted));
+        let mut tampered = data.clone();
+        tampered[HEADER.len() + ROUNDS_LEN] ^= 1;
+        assert_eq!(
+            key("correct horse").decrypt(&tampered),
+            Err(CryptErr::WrongKey)
+        );
+        let zero_rounds = [HEADER, &[0; ROUNDS_LEN + SALT_LEN + NONCE_LEN]].concat();
+        assert_eq!(wrong.decrypt(&zero_rounds), Err(CryptErr::NotEncrypted));
+    }
+
+    #[test]
+    fn test_too_many_rounds() {
+        let mut data = key("correct horse").encrypt(b"1\n");
+        let rounds = HEADER.len()..HEADER.len() + ROUNDS_LEN;
+        data[rounds.clone()].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert_eq!(
+            key("correct horse").decrypt(&data),
+            Err(CryptErr::TooManyRounds(u32::MAX))
+        );
+        data[rounds].copy_from_slice(&(Key::MAX_ROUNDS + 1).to_be_bytes());
+        assert_eq!(
+            key("correct horse").decrypt(&data),
+            Err(CryptErr::TooManyRounds(Key::MAX_ROUNDS + 1))
+        );
+        let capped = Key::from_passphrase("correct horse").with_rounds(u32::MAX);
+        assert_eq!(capped.rounds, Key::MAX_ROUNDS);
+    }
+
+    #[test]
+    fn test_paths() {
+        let path = encrypted_path(Path::new("day01/puzzle/input.txt"));
+        assert_eq!(path, Path::new("day01/puzzle/input.txt.enc"));
+        assert!(is_encrypted(&path));
+        assert!(!is_encrypted(Path::new("day01/puzzle/input.txt")));
+    }
+}
@@ -0,0 +1,299 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+/// A cycle in a sequence of states: the sequence enters the loop after `start` steps and then
+/// repeats every `length` steps.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// Maps step `n` onto the earliest step that has the same state.
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            return n;
+        }
+        self.start + (n - self.start) % self.length
+    }
+}
+
+/// Floyd's tortoise and hare. Only needs `PartialEq` and keeps two states in memory, but calls
+/// `next` roughly three times as often as the hashing approach.
+pub fn floyd<T, F>(initial: T, next: F) -> Cycle
+where
+    T: PartialEq + Clone,
+    F: Fn(&T) -> T,
+{
+    let mut tortoise = next(&initial);
+    let mut hare = next(&tortoise);
+    while tortoise != hare {
+        tortoise = next(&tortoise);
+        hare = next(&next(&hare));
+    }
+
+    let mut start = 0;
+    let mut tortoise = initial;
+    while tortoise != hare {
+        tortoise = next(&tortoise);
+        hare = next(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    let mut hare = next(&tortoise);
+    while tortoise != hare {
+        hare = next(&hare);
+        length += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Brent's algorithm. Same memory use as Floyd, but finds the length first and usually needs
+/// fewer calls to `next`.
+pub fn brent<T, F>(initial: T, next: F) -> Cycle
+where
+    T: PartialEq + Clone,
+    F: Fn(&T) -> T,
+{
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = next(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = next(&hare);
+        length += 1;
+    }
+
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..length {
+        hare = next(&hare);
+    }
+
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = next(&tortoise);
+        hare = next(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Records every state until one repeats. `next` returns `None` when the sequence ends, in
+/// which case there is no cycle.
+pub fn find_cycle<T, F>(initial: T, mut next: F) -> Option<Cycle>
+where
+    T: Hash + Eq + Clone,
+    F: FnMut(&T) -> Option<T>,
+{
+    let mut seen = HashMap::new();
+    let mut state = initial;
+    let mut step = 0;
+    loop {
+        if let Some(&start) = seen.get(&state) {
+            return Some(Cycle {
+                start,
+                length: step - start,
+            });
+        }
+        let following = next(&state)?;
+        seen.insert(state, step);
+        state = following;
+        step += 1;
+    }
+}
+
+/// The states a sequence has been through, for `find_cycle_in`. Only membership is asked for, so
+/// a dense grid can stand in for a hash set.
+pub trait Visited<T> {
+    /// Records `state`, returning false if it had already been recorded.
+    fn insert(&mut self, state: &T) -> bool;
+}
+
+impl<T: Hash + Eq + Clone> Visited<T> for HashSet<T> {
+    fn insert(&mut self, state: &T) -> bool {
+        HashSet::insert(self, state.clone())
+    }
+}
+
+/// Whether the sequence ever comes back to a state, recording states in `visited`, which should
+/// start out empty. Stops at the first repeat, without working out where the loop begins.
+#[inline]
+pub fn revisits<T, F, V>(initial: T, mut next: F, visited: &mut V) -> bool
+where
+    F: FnMut(&T) -> Option<T>,
+    V: Visited<T>,
+{
+    let mut state = initial;
+    while visited.insert(&state) {
+        match next(&state) {
+            Some(following) => state = following,
+            None => return false,
+        }
+    }
+    true
+}
+
+/// `find_cycle`, recording states in `visited` instead of a map of steps, which should start out
+/// empty. Once a state repeats, the sequence is run again from the start to find where the loop
+/// begins.
+pub fn find_cycle_in<T, F, V>(initial: T, mut next: F, visited: &mut V) -> Option<Cycle>
+where
+    T: PartialEq + Clone,
+    F: FnMut(&T) -> Option<T>,
+    V: Visited<T>,
+{
+    let mut state = initial.clone();
+    let mut step = 0;
+    while visited.insert(&state) {
+        state = next(&state)?;
+        step += 1;
+    }
+    let mut earlier = initial;
+    let mut start = 0;
+    while earlier != state {
+        earlier = next(&earlier)?;
+        start += 1;
+    }
+    Some(Cycle {
+        start,
+        length: step - start,
+    })
+}
+
+/// Returns the state after `n` applications of `next`, skipping whole periods once a cycle has
+/// been found. Only stores states until the first repeat.
+pub fn state_at<T, F>(initial: T, mut next: F, n: usize) -> T
+where
+    T: Hash + Eq + Clone,
+    F: FnMut(&T) -> T,
+{
+    let mut seen = HashMap::new();
+    let mut history = vec![];
+    let mut state = initial;
+    for step in 0..n {
+        if let Some(&start) = seen.get(&state) {
+            let cycle = Cycle {
+                start,
+                length: step - start,
+            };
+            return history.swap_remove(cycle.equivalent_step(n));
+        }
+        seen.insert(state.clone(), step);
+        let following = next(&state);
+        history.push(state);
+        state = following;
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0, 1, 2, 3, 4, 5, 6, 7, 3, 4, ...
+    fn rho(x: &u32) -> u32 {
+        if *x == 7 {
+            3
+        } else {
+            x + 1
+        }
+    }
+
+    #[test]
+    fn test_floyd() {
+        let expected = Cycle {
+            start: 3,
+            length: 5,
+        };
+        assert_eq!(floyd(0, rho), expected);
+    }
+
+    #[test]
+    fn test_brent() {
+        let expected = Cycle {
+            start: 3,
+            length: 5,
+        };
+        assert_eq!(brent(0, rho), expected);
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let expected = Cycle {
+            start: 3,
+            length: 5,
+        };
+        assert_eq!(find_cycle(0, |x| Some(rho(x))), Some(expected));
+    }
+
+    #[test]
+    fn test_find_cycle_terminates() {
+        assert_eq!(find_cycle(0, |&x| (x < 10).then_some(x + 1)), None);
+    }
+
+    #[test]
+    fn test_find_cycle_in() {
+        let expected = Cycle {
+            start: 3,
+            length: 5,
+        };
+        let mut visited = HashSet::new();
+        assert_eq!(find_cycle_in(0, |x| Some(rho(x)), &mut visited), Some(expected));
+        assert_eq!(visited.len(), 8);
+        let mut visited = HashSet::new();
+        let ends = |&x: &u32| (x < 10).then_some(x + 1);
+        assert_eq!(find_cycle_in(0, ends, &mut visited), None);
+    }
+
+    #[test]
+    fn test_revisits() {
+        let mut visited = HashSet::new();
+        assert!(revisits(0, |x| Some(rho(x)), &mut visited));
+        assert_eq!(visited.len(), 8);
+        let mut visited = HashSet::new();
+        assert!(!revisits(0, |&x: &u32| (x < 10).then_some(x + 1), &mut visited));
+        assert_eq!(visited.len(), 11);
+    }
+
+    #[test]
+    fn test_pure_loop() {
+        let expected = Cycle {
+            start: 0,
+            length: 4,
+        };
+        let next = |x: &u32| (x + 1) % 4;
+        assert_eq!(floyd(0, next), expected);
+        assert_eq!(brent(0, next), expected);
+        assert_eq!(find_cycle(0, |x| Some(next(x))), Some(expected));
+    }
+
+    #[test]
+    fn test_equivalent_step() {
+        let cycle = Cycle {
+            start: 3,
+            length: 5,
+        };
+        assert_eq!(cycle.equivalent_step(2), 2);
+        assert_eq!(cycle.equivalent_step(8), 3);
+        assert_eq!(cycle.equivalent_step(1_000_000_000), 5);
+    }
+
+    #[test]
+    fn test_state_at() {
+        assert_eq!(state_at(0, rho, 5), 5);
+        assert_eq!(state_at(0, rho, 8), 3);
+        assert_eq!(state_at(0, rho, 1_000_000_000), 5);
+    }
+}
@@ -0,0 +1,192 @@
+//! Example puzzles with their parameters and expected answers kept beside them.
+//!
+//! Each `puzzle/<name>.txt` example may have a `puzzle/<name>.meta` sidecar of `key = value`
+//! lines. `part_a` and `part_b` hold the expected answers, written as the runner prints them, and
+//! any other key is a parameter for the solver, such as a grid size or a threshold. Blank lines
+//! and lines starting with `#` are ignored.
+//!
+//! ```text
+//! # Smaller floor than the real puzzle
+//! width = 11
+//! height = 7
+//! part_a = 12
+//! ```
+
+use std::{collections::BTreeMap, fs, path::Path, str::FromStr};
+
+pub const EXTENSION: &str = "meta";
+pub const PUZZLE_DIR: &str = "puzzle";
+pub const PARTS: [&str; 2] = ["A", "B"];
+
+#[derive(Debug, PartialEq)]
+pub enum ExampleErr {
+    Io(String),
+    BadLine(String, usize),
+    MissingParam(String),
+    BadParam(String, String),
+    UnknownPart(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Example {
+    pub name: String,
+    pub puzzle: String,
+    params: BTreeMap<String, String>,
+}
+
+impl Example {
+    pub fn parse(name: &str, puzzle: &str, meta: &str) -> Result<Example, ExampleErr> {
+        let mut params = BTreeMap::new();
+        for (idx, line) in meta.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| ExampleErr::BadLine(name.to_string(), idx + 1))?;
+            params.insert(key.trim().to_string(), value.trim().to_string());
+        }
+        Ok(Example {
+            name: name.to_string(),
+            puzzle: puzzle.to_string(),
+            params,
+        })
+    }
+
+    /// Loads an example from its puzzle file and the sidecar next to it.
+    pub fn load(puzzle_path: &Path) -> Result<Example, ExampleErr> {
+        let read = |path: &Path| {
+            fs::read_to_string(path)
+                .map_err(|e| ExampleErr::Io(format!("{}: {}", path.display(), e)))
+        };
+        let name = puzzle_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let meta = read(&puzzle_path.with_extension(EXTENSION))?;
+        Example::parse(&name, &read(puzzle_path)?, &meta)
+    }
+
+    pub fn param<T: FromStr>(&self, key: &str) -> Result<T, ExampleErr> {
+        let value = self
+            .params
+            .get(key)
+            .ok_or_else(|| ExampleErr::MissingParam(key.to_string()))?;
+        value
+            .parse()
+            .map_err(|_| ExampleErr::BadParam(key.to_string(), value.clone()))
+    }
+
+    pub fn expected(&self, part: &str) -> Option<&str> {
+        self.params
+            .get(&format!("part_{}", part.to_lowercase()))
+            .map(|s| s.as_str())
+    }
+}
+
+/// Every example in `dir` that has a sidecar, ordered by name.
+pub fn load_all<P: AsRef<Path>>(dir: P) -> Result<Vec<Example>, ExampleErr> {
+    let dir = dir.as_ref();
+    let entries =
+        fs::read_dir(dir).map_err(|e| ExampleErr::Io(format!("{}: {}", dir.display(), e)))?;
+    let mut examples = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == EXTENSION))
+        .map(|meta| Example::load(&meta.with_extension("txt")))
+        .collect::<Result<Vec<_>, _>>()?;
+    examples.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(examples)
+}
+
+/// Runs `solve` for every part each example has an answer for. Returns how many answers were
+/// checked, or a line describing each one that did not match.
+pub fn check<F>(examples: &[Example], solve: F) -> Result<usize, Vec<String>>
+where
+    F: Fn(&Example, &str) -> Result<String, ExampleErr>,
+{
+    let mut checked = 0;
+    let mut failures = vec![];
+    for example in examples {
+        for part in PARTS {
+            let Some(expected) = example.expected(part) else {
+                continue;
+            };
+            checked += 1;
+            match solve(example, part) {
+                Ok(answer) if answer == expected => (),
+                Ok(answer) => failures.push(format!(
+                    "{} part {}: expected {}, got {}",
+                    example.name, part, expected, answer
+                )),
+                Err(err) => failures.push(format!("{} part {}: {:?}", example.name, part, err)),
+            }
+        }
+    }
+    if failures.is_empty() {
+        return Ok(checked);
+    }
+    Err(failures)
+}
+
+/// Checks every example in a day's puzzle directory, for use from its tests as
+/// `check_all(env!("CARGO_MANIFEST_DIR"), solve)`. Panics listing each mismatch, or if there was
+/// nothing to check.
+pub fn check_all<P, F>(crate_dir: P, solve: F)
+where
+    P: AsRef<Path>,
+    F: Fn(&Example, &str) -> Result<String, ExampleErr>,
+{
+    let dir = crate_dir.as_ref().join(PUZZLE_DIR);
+    let examples = load_all(&dir).expect("Could not load examples");
+    match check(&examples, solve) {
+        Ok(0) => panic!("No expected answers in {}", dir.display()),
+        Ok(_) => (),
+        Err(failures) => panic!("Examples failed:\n{}", failures.join("\n")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const META: &str = "# Smaller floor\nwidth = 11\nheight=7\n\npart_a = 12\n";
+
+    #[test]
+    fn test_parse() {
+        let example = Example::parse("test", "p=0,4 v=3,-3", META).unwrap();
+        assert_eq!(example.param::<i32>("width"), Ok(11));
+        assert_eq!(example.param::<i32>("height"), Ok(7));
+        assert_eq!(example.expected("A"), Some("12"));
+        assert_eq!(example.expected("B"), None);
+        assert_eq!(
+            example.param::<i32>("depth"),
+            Err(ExampleErr::MissingParam("depth".to_string()))
+        );
+        assert_eq!(
+            Example::parse("test", "", "width 11"),
+            Err(ExampleErr::BadLine("test".to_string(), 1))
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let examples = vec![
+            Example::parse("first", "3", "part_a = 6\npart_b = 9").unwrap(),
+            Example::parse("second", "4", "part_a = 8\npart_b = 10").unwrap(),
+        ];
+        let solve = |example: &Example, part: &str| {
+            let n: usize = example.puzzle.parse().unwrap();
+            match part {
+                "A" => Ok((n * 2).to_string()),
+                "B" => Ok((n * 3).to_string()),
+                _ => Err(ExampleErr::UnknownPart(part.to_string())),
+            }
+        };
+        assert_eq!(
+            check(&examples, solve),
+            Err(vec!["second part B: expected 10, got 12".to_string()])
+        );
+        assert_eq!(check(&examples[..1], solve), Ok(2));
+    }
+}
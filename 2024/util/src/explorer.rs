@@ -0,0 +1,501 @@
+//! Step-through debugging for grid simulations.
+//!
+//! A day implements `Simulation` for its state, and an `Explorer` moves back and forth through
+//! the steps in response to `Command`s. Past states are not all kept: a `StateLog` stores a
+//! checkpoint every few steps and replays from the nearest one, so long runs such as a full
+//! warehouse program stay cheap. Everything here is headless; the terminal front end is in
+//! `tui` behind the `tui` feature.
+
+use std::{fmt, str::FromStr};
+
+use crate::point::Point;
+
+/// A character grid showing one state of a simulation, indexed by `Point(row, col)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    rows: Vec<Vec<char>>,
+}
+
+impl Frame {
+    pub fn new(height: usize, width: usize, fill: char) -> Frame {
+        Frame {
+            rows: vec![vec![fill; width]; height],
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn width(&self) -> usize {
+        self.rows.first().map_or(0, |row| row.len())
+    }
+
+    pub fn get(&self, point: &Point) -> Option<char> {
+        let row = self.rows.get(usize::try_from(point.0).ok()?)?;
+        row.get(usize::try_from(point.1).ok()?).copied()
+    }
+
+    /// Sets a cell, ignoring points outside the frame.
+    pub fn set(&mut self, point: &Point, cell: char) {
+        let (Ok(row), Ok(col)) = (usize::try_from(point.0), usize::try_from(point.1)) else {
+            return;
+        };
+        if let Some(c) = self.rows.get_mut(row).and_then(|r| r.get_mut(col)) {
+            *c = cell;
+        }
+    }
+
+    pub fn rows(&self) -> &[Vec<char>] {
+        &self.rows
+    }
+
+    pub fn count(&self, cell: char) -> usize {
+        self.rows.iter().flatten().filter(|&&c| c == cell).count()
+    }
+}
+
+impl From<&str> for Frame {
+    fn from(text: &str) -> Frame {
+        Frame {
+            rows: text.lines().map(|line| line.chars().collect()).collect(),
+        }
+    }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in &self.rows {
+            writeln!(f, "{}", row.iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+pub trait Simulation: Clone {
+    /// Advances one step, returning false without changing anything once the simulation has
+    /// finished.
+    fn step(&mut self) -> bool;
+
+    fn frame(&self) -> Frame;
+}
+
+/// Every state of a simulation from its start, reconstructed on demand from checkpoints.
+pub struct StateLog<S> {
+    checkpoints: Vec<S>,
+    interval: usize,
+    end: Option<usize>,
+}
+
+impl<S: Simulation> StateLog<S> {
+    pub const DEFAULT_INTERVAL: usize = 64;
+
+    pub fn new(initial: S, interval: usize) -> StateLog<S> {
+        StateLog {
+            checkpoints: vec![initial],
+            interval: interval.max(1),
+            end: None,
+        }
+    }
+
+    /// The last step, once the simulation has been run far enough to find it.
+    pub fn end(&self) -> Option<usize> {
+        self.end
+    }
+
+    /// Steps `state` on from `at` towards `target`, recording checkpoints along the way. Stops
+    /// early at the end of the simulation, returning the step actually reached.
+    fn run_from(&mut self, mut at: usize, mut state: S, target: usize) -> (usize, S) {
+        while at < target {
+            if !state.step() {
+                self.end = Some(at);
+                break;
+            }
+            at += 1;
+            if at.is_multiple_of(self.interval) && at / self.interval == self.checkpoints.len() {
+                self.checkpoints.push(state.clone());
+            }
+        }
+        (at, state)
+    }
+
+    /// The state after `step` steps, or the final state if the simulation ends sooner.
+    pub fn state_at(&mut self, step: usize) -> (usize, S) {
+        let step = self.end.map_or(step, |end| step.min(end));
+        let idx = (step / self.interval).min(self.checkpoints.len() - 1);
+        let start = self.checkpoints[idx].clone();
+        self.run_from(idx * self.interval, start, step)
+    }
+
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CommandErr {
+    Empty,
+    UnknownCommand(String),
+    BadArgument(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Forward(usize),
+    Back(usize),
+    Goto(usize),
+    /// Steps forward at least once, until the named metric is above the threshold, giving up
+    /// after `limit` steps.
+    Until {
+        metric: String,
+        above: f64,
+        limit: usize,
+    },
+    Mark(Point),
+    Unmark,
+}
+
+impl Command {
+    pub const DEFAULT_UNTIL_LIMIT: usize = 100_000;
+}
+
+impl FromStr for Command {
+    type Err = CommandErr;
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (&name, args) = words.split_first().ok_or(CommandErr::Empty)?;
+        let arg = |idx: usize, default: Option<usize>| -> Result<usize, CommandErr> {
+            match args.get(idx) {
+                Some(value) => value
+                    .parse()
+                    .map_err(|_| CommandErr::BadArgument(value.to_string())),
+                None => default.ok_or(CommandErr::BadArgument(line.to_string())),
+            }
+        };
+        match name {
+            "forward" | "f" => Ok(Command::Forward(arg(0, Some(1))?)),
+            "back" | "b" => Ok(Command::Back(arg(0, Some(1))?)),
+            "goto" | "g" => Ok(Command::Goto(arg(0, None)?)),
+            "until" | "u" => {
+                let metric = args.first().ok_or(CommandErr::BadArgument(line.to_string()))?;
+                let above = args
+                    .get(1)
+                    .and_then(|v| v.parse().ok())
+                    .ok_or(CommandErr::BadArgument(line.to_string()))?;
+                Ok(Command::Until {
+                    metric: metric.to_string(),
+                    above,
+                    limit: arg(2, Some(Command::DEFAULT_UNTIL_LIMIT))?,
+                })
+            }
+            "mark" | "m" => {
+                let (row, col) = (arg(0, None)?, arg(1, None)?);
+                Ok(Command::Mark(Point(row as i32, col as i32)))
+            }
+            "unmark" => Ok(Command::Unmark),
+            _ => Err(CommandErr::UnknownCommand(name.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ExplorerErr {
+    UnknownMetric(String),
+    NotReached { metric: String, step: usize },
+}
+
+type Metric<'a, S> = Box<dyn Fn(&S) -> f64 + 'a>;
+
+/// A position in a simulation's history, plus the named metrics and marked cell shown with it.
+pub struct Explorer<'a, S> {
+    log: StateLog<S>,
+    step: usize,
+    state: S,
+    metrics: Vec<(&'static str, Metric<'a, S>)>,
+    marked: Option<Point>,
+    /// Where the marked cell changed, recorded through `recorded` so far.
+    history: Vec<(usize, char)>,
+    recorded: Option<usize>,
+}
+
+impl<'a, S: Simulation> Explorer<'a, S> {
+    pub fn new(initial: S) -> Explorer<'a, S> {
+        Explorer {
+            log: StateLog::new(initial.clone(), StateLog::<S>::DEFAULT_INTERVAL),
+            step: 0,
+            state: initial,
+            metrics: vec![],
+            marked: None,
+            history: vec![],
+            recorded: None,
+        }
+    }
+
+    /// Adds a value worth watching, which `Command::Until` can also stop on.
+    pub fn with_metric<F>(mut self, name: &'static str, metric: F) -> Explorer<'a, S>
+    where
+        F: Fn(&S) -> f64 + 'a,
+    {
+        self.metrics.push((name, Box::new(metric)));
+        self
+    }
+
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    pub fn end(&self) -> Option<usize> {
+        self.log.end()
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    pub fn frame(&self) -> Frame {
+        self.state.frame()
+    }
+
+    pub fn marked(&self) -> Option<Point> {
+        self.marked
+    }
+
+    pub fn metrics(&self) -> Vec<(&'static str, f64)> {
+        self.metrics
+            .iter()
+            .map(|(name, metric)| (*name, metric(&self.state)))
+            .collect()
+    }
+
+    fn goto(&mut self, step: usize) {
+        (self.step, self.state) = if step >= self.step {
+            self.log.run_from(self.step, self.state.clone(), step)
+        } else {
+            self.log.state_at(step)
+        };
+    }
+
+    pub fn apply(&mut self, command: &Command) -> Result<(), ExplorerErr> {
+        match command {
+            Command::Forward(n) => self.goto(self.step.saturating_add(*n)),
+            Command::Back(n) => self.goto(self.step.saturating_sub(*n)),
+            Command::Goto(step) => self.goto(*step),
+            Command::Until {
+                metric,
+                above,
+                limit,
+            } => {
+                let (_, measure) = self
+                    .metrics
+                    .iter()
+                    .find(|(name, _)| name == metric)
+                    .ok_or_else(|| ExplorerErr::UnknownMetric(metric.clone()))?;
+                let start = self.step;
+                let (mut at, mut state) = (self.step, self.state.clone());
+                let reached = loop {
+                    let previous = at;
+                    (at, state) = self.log.run_from(at, state, at + 1);
+                    if measure(&state) > *above {
+                        break Ok(());
+                    }
+                    if at == previous || at - start >= *limit {
+                        break Err(ExplorerErr::NotReached {
+                            metric: metric.clone(),
+                            step: at,
+                        });
+                    }
+                };
+                (self.step, self.state) = (at, state);
+                return reached;
+            }
+            Command::Mark(point) => self.mark(Some(*point)),
+            Command::Unmark => self.mark(None),
+        }
+        Ok(())
+    }
+
+    fn mark(&mut self, point: Option<Point>) {
+        self.marked = point;
+        self.history.clear();
+        self.recorded = None;
+    }
+
+    fn record(&mut self, at: usize, state: &S) {
+        let Some(point) = self.marked else {
+            return;
+        };
+        let cell = state.frame().get(&point).unwrap_or(' ');
+        if self.history.last().is_none_or(|&(_, last)| last != cell) {
+            self.history.push((at, cell));
+        }
+        self.recorded = Some(at);
+    }
+
+    /// Each step up to the current one where the marked cell changed, with its new contents.
+    /// Steps already looked at are kept, so only those past the furthest yet are run, starting
+    /// from the nearest checkpoint.
+    pub fn history(&mut self) -> Vec<(usize, char)> {
+        if self.marked.is_none() {
+            return vec![];
+        }
+        if self.recorded.is_none_or(|recorded| recorded < self.step) {
+            let (mut at, mut state) = self.log.state_at(self.recorded.unwrap_or(0));
+            if self.recorded.is_none() {
+                self.record(at, &state);
+            }
+            while at < self.step {
+                let previous = at;
+                (at, state) = self.log.run_from(at, state, at + 1);
+                if at == previous {
+                    break;
+                }
+                self.record(at, &state);
+            }
+        }
+        let step = self.step;
+        self.history.iter().filter(|&&(at, _)| at <= step).copied().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A dot walking right along a row, stopping at the end.
+    #[derive(Debug, Clone)]
+    struct Walker {
+        col: usize,
+        width: usize,
+    }
+
+    impl Simulation for Walker {
+        fn step(&mut self) -> bool {
+            if self.col + 1 >= self.width {
+                return false;
+            }
+            self.col += 1;
+            true
+        }
+
+        fn frame(&self) -> Frame {
+            let mut frame = Frame::new(1, self.width, '.');
+            frame.set(&Point(0, self.col as i32), '@');
+            frame
+        }
+    }
+
+    fn explorer<'a>() -> Explorer<'a, Walker> {
+        Explorer::new(Walker { col: 0, width: 200 }).with_metric("col", |w| w.col as f64)
+    }
+
+    #[test]
+    fn test_frame() {
+        let mut frame = Frame::from("#.\n.#\n");
+        assert_eq!(frame.get(&Point(1, 1)), Some('#'));
+        assert_eq!(frame.get(&Point(-1, 1)), None);
+        frame.set(&Point(0, 1), 'O');
+        frame.set(&Point(5, 5), 'O');
+        assert_eq!(frame.to_string(), "#O\n.#\n");
+        assert_eq!(frame.count('#'), 2);
+    }
+
+    #[test]
+    fn test_state_log() {
+        let mut log = StateLog::new(Walker { col: 0, width: 10 }, 4);
+        assert_eq!(log.state_at(6).1.col, 6);
+        assert_eq!(log.checkpoints.len(), 2);
+        assert_eq!(log.state_at(2).1.col, 2);
+        let (at, state) = log.state_at(50);
+        assert_eq!((at, state.col), (9, 9));
+        assert_eq!(log.end(), Some(9));
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!("f".parse(), Ok(Command::Forward(1)));
+        assert_eq!("back 20".parse(), Ok(Command::Back(20)));
+        assert_eq!("g 7083".parse(), Ok(Command::Goto(7083)));
+        assert_eq!(
+            "until density 0.3".parse(),
+            Ok(Command::Until {
+                metric: "density".to_string(),
+                above: 0.3,
+                limit: Command::DEFAULT_UNTIL_LIMIT
+            })
+        );
+        assert_eq!("mark 3 4".parse(), Ok(Command::Mark(Point(3, 4))));
+        assert_eq!("goto".parse::<Command>(), Err(CommandErr::BadArgument("goto".to_string())));
+        assert_eq!("f x".parse::<Command>(), Err(CommandErr::BadArgument("x".to_string())));
+        assert_eq!("jump".parse::<Command>(), Err(CommandErr::UnknownCommand("jump".to_string())));
+        assert_eq!("".parse::<Command>(), Err(CommandErr::Empty));
+    }
+
+    #[test]
+    fn test_step_forward_and_back() {
+        let mut explorer = explorer();
+        explorer.apply(&Command::Forward(150)).unwrap();
+        assert_eq!(explorer.step(), 150);
+        explorer.apply(&Command::Back(100)).unwrap();
+        assert_eq!(explorer.step(), 50);
+        assert_eq!(explorer.frame().get(&Point(0, 50)), Some('@'));
+        explorer.apply(&Command::Goto(500)).unwrap();
+        assert_eq!(explorer.step(), 199);
+        assert_eq!(explorer.end(), Some(199));
+        explorer.apply(&Command::Back(500)).unwrap();
+        assert_eq!(explorer.step(), 0);
+    }
+
+    #[test]
+    fn test_until() {
+        let mut explorer = explorer();
+        let until = |above, limit| Command::Until {
+            metric: "col".to_string(),
+            above,
+            limit,
+        };
+        explorer.apply(&until(41.5, 100)).unwrap();
+        assert_eq!(explorer.step(), 42);
+        assert_eq!(explorer.metrics(), vec![("col", 42.0)]);
+        assert_eq!(
+            explorer.apply(&until(100.0, 10)),
+            Err(ExplorerErr::NotReached {
+                metric: "col".to_string(),
+                step: 52
+            })
+        );
+        assert_eq!(
+            explorer.apply(&until(500.0, 1000)),
+            Err(ExplorerErr::NotReached {
+                metric: "col".to_string(),
+                step: 199
+            })
+        );
+        let unknown = Command::Until {
+            metric: "row".to_string(),
+            above: 0.0,
+            limit: 1,
+        };
+        assert_eq!(
+            explorer.apply(&unknown),
+            Err(ExplorerErr::UnknownMetric("row".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_history() {
+        let mut explorer = explorer();
+        assert!(explorer.history().is_empty());
+        explorer.apply(&Command::Mark(Point(0, 3))).unwrap();
+        explorer.apply(&Command::Goto(10)).unwrap();
+        assert_eq!(explorer.history(), vec![(0, '.'), (3, '@'), (4, '.')]);
+        explorer.apply(&Command::Back(8)).unwrap();
+        assert_eq!(explorer.history(), vec![(0, '.')]);
+        explorer.apply(&Command::Goto(150)).unwrap();
+        assert_eq!(explorer.history(), vec![(0, '.'), (3, '@'), (4, '.')]);
+        assert_eq!(explorer.recorded, Some(150));
+        explorer.apply(&Command::Mark(Point(0, 160))).unwrap();
+        assert_eq!(explorer.history(), vec![(0, '.')]);
+        explorer.apply(&Command::Goto(500)).unwrap();
+        assert_eq!(explorer.history(), vec![(0, '.'), (160, '@'), (161, '.')]);
+        assert_eq!(explorer.recorded, Some(199));
+        explorer.apply(&Command::Unmark).unwrap();
+        assert_eq!(explorer.marked(), None);
+    }
+}
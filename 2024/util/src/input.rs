@@ -0,0 +1,114 @@
+//! Where a day reads its puzzle from.
+//!
+//! Each day takes an optional input path on the command line, with `-` meaning stdin, and
+//! otherwise reads `puzzle/input.txt` beside its manifest. Days that can work line by line take
+//! any `BufRead`, so large generated inputs can be piped straight in. An input that is only
+//! stored encrypted is decrypted in memory, see `crypt`.
+
+use std::{
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Cursor},
+    path::Path,
+};
+
+use crate::crypt::{self, Key};
+
+pub const STDIN: &str = "-";
+pub const INPUT_FILE: &str = "input.txt";
+
+/// The path of the calling crate's `puzzle/input.txt`.
+#[macro_export]
+macro_rules! input_path {
+    () => {
+        concat!(env!("CARGO_MANIFEST_DIR"), "/puzzle/input.txt")
+    };
+}
+
+fn with_path(path: &Path, err: io::Error) -> io::Error {
+    io::Error::new(err.kind(), format!("{}: {}", path.display(), err))
+}
+
+/// Decrypts an encrypted input into memory.
+fn open_encrypted(path: &Path) -> io::Result<Box<dyn BufRead>> {
+    let key = Key::find().ok_or_else(|| {
+        let msg = format!("no key in {} or {}", crypt::KEY_VAR, crypt::key_file().display());
+        with_path(path, io::Error::new(io::ErrorKind::PermissionDenied, msg))
+    })?;
+    let data = fs::read(path).map_err(|err| with_path(path, err))?;
+    let plain = key.decrypt(&data).map_err(|err| {
+        with_path(path, io::Error::new(io::ErrorKind::InvalidData, format!("{:?}", err)))
+    })?;
+    Ok(Box::new(Cursor::new(plain)))
+}
+
+/// Opens `path` for buffered reading, or stdin if it is `-`. If `path` is missing but an
+/// encrypted copy is beside it, that is decrypted instead.
+pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Box<dyn BufRead>> {
+    let path = path.as_ref();
+    if path == Path::new(STDIN) {
+        return Ok(Box::new(io::stdin().lock()));
+    }
+    if crypt::is_encrypted(path) {
+        return open_encrypted(path);
+    }
+    let encrypted = crypt::encrypted_path(path);
+    if !path.exists() && encrypted.exists() {
+        return open_encrypted(&encrypted);
+    }
+    let file = File::open(path).map_err(|err| with_path(path, err))?;
+    Ok(Box::new(BufReader::new(file)))
+}
+
+/// Reads everything left in `reader`, for days that need the whole puzzle at once.
+pub fn read_to_string<R: BufRead>(mut reader: R) -> io::Result<String> {
+    let mut puzzle = String::new();
+    reader.read_to_string(&mut puzzle)?;
+    Ok(puzzle)
+}
+
+/// A day's real input for its tests, as `real_input(env!("CARGO_MANIFEST_DIR"))`. Returns
+/// `None`, so the test can skip, if the input is only stored encrypted and there is no key.
+pub fn real_input<P: AsRef<Path>>(crate_dir: P) -> Option<String> {
+    let path = crate_dir.as_ref().join("puzzle").join(INPUT_FILE);
+    if !path.exists() && Key::find().is_none() {
+        eprintln!("skipping {}: encrypted and no key", path.display());
+        return None;
+    }
+    let puzzle = open(&path).and_then(read_to_string);
+    Some(puzzle.unwrap_or_else(|err| panic!("Unable to read real input: {}", err)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open() {
+        let puzzle = open(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml")).unwrap();
+        assert!(read_to_string(puzzle).unwrap().starts_with("[package]"));
+        let err = open("no/such/input.txt").err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(err.to_string().starts_with("no/such/input.txt: "));
+    }
+
+    #[test]
+    fn test_open_encrypted() {
+        let dir = std::env::temp_dir().join(format!("aoc-input-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(INPUT_FILE);
+        let key = Key::from_passphrase("not the real key").with_rounds(1000);
+        fs::write(crypt::encrypted_path(&path), key.encrypt(b"1\n2\n")).unwrap();
+
+        let result = open(&path).and_then(read_to_string);
+        match Key::find() {
+            Some(_) => assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData),
+            None => assert_eq!(result.unwrap_err().kind(), io::ErrorKind::PermissionDenied),
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_to_string() {
+        assert_eq!(read_to_string("1\n2\n".as_bytes()).unwrap(), "1\n2\n");
+    }
+}
@@ -0,0 +1,226 @@
+use std::{collections::BTreeMap, ops::Range};
+
+/// A set of integers stored as sorted, non-overlapping, non-adjacent half-open ranges.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RangeSet {
+    // start -> end
+    spans: BTreeMap<i64, i64>,
+}
+
+impl RangeSet {
+    pub fn new() -> RangeSet {
+        RangeSet::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Number of separate spans in the set.
+    pub fn span_count(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Number of integers covered by the set.
+    pub fn covered(&self) -> i64 {
+        self.spans.iter().map(|(start, end)| end - start).sum()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Range<i64>> + '_ {
+        self.spans.iter().map(|(&start, &end)| start..end)
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        self.span_containing(value).is_some()
+    }
+
+    pub fn span_containing(&self, value: i64) -> Option<Range<i64>> {
+        self.spans
+            .range(..=value)
+            .next_back()
+            .filter(|(_, &end)| value < end)
+            .map(|(&start, &end)| start..end)
+    }
+
+    /// Adds a range, merging it with any spans it overlaps or touches.
+    pub fn insert(&mut self, range: Range<i64>) {
+        if range.is_empty() {
+            return;
+        }
+        let mut start = range.start;
+        let mut end = range.end;
+        if let Some((&prev_start, &prev_end)) = self.spans.range(..=start).next_back() {
+            if prev_end >= start {
+                start = prev_start;
+                end = end.max(prev_end);
+            }
+        }
+        let absorbed = self
+            .spans
+            .range(start..=end)
+            .map(|(&s, &e)| (s, e))
+            .collect::<Vec<_>>();
+        for (s, e) in absorbed {
+            end = end.max(e);
+            self.spans.remove(&s);
+        }
+        self.spans.insert(start, end);
+    }
+
+    /// Removes a range, splitting any span that straddles either end of it.
+    pub fn remove(&mut self, range: Range<i64>) {
+        if range.is_empty() {
+            return;
+        }
+        if let Some((&prev_start, &prev_end)) = self.spans.range(..range.start).next_back() {
+            if prev_end > range.start {
+                self.spans.insert(prev_start, range.start);
+                if prev_end > range.end {
+                    self.spans.insert(range.end, prev_end);
+                }
+            }
+        }
+        let overlapped = self
+            .spans
+            .range(range.start..range.end)
+            .map(|(&s, &e)| (s, e))
+            .collect::<Vec<_>>();
+        for (s, e) in overlapped {
+            self.spans.remove(&s);
+            if e > range.end {
+                self.spans.insert(range.end, e);
+            }
+        }
+    }
+
+    /// Breaks the set in two: `self` keeps everything below `at`, the rest is returned.
+    pub fn split_off(&mut self, at: i64) -> RangeSet {
+        let mut upper = self.spans.split_off(&at);
+        if let Some(end) = self.spans.values_mut().next_back() {
+            if *end > at {
+                upper.insert(at, *end);
+                *end = at;
+            }
+        }
+        RangeSet { spans: upper }
+    }
+
+    /// Adds every span of `other` to this set.
+    pub fn merge(&mut self, other: &RangeSet) {
+        other.iter().for_each(|range| self.insert(range));
+    }
+
+    /// The first span holding at least `len` values, searching from the lowest.
+    pub fn first_fit(&self, len: i64) -> Option<Range<i64>> {
+        self.iter().find(|range| range.end - range.start >= len)
+    }
+
+    /// The first uncovered stretch of at least `len` values inside `bounds`.
+    pub fn first_gap(&self, len: i64, bounds: Range<i64>) -> Option<Range<i64>> {
+        let mut gap_start = bounds.start;
+        for span in self.iter() {
+            if span.end <= gap_start {
+                continue;
+            }
+            let gap_end = span.start.min(bounds.end);
+            if gap_end - gap_start >= len {
+                return Some(gap_start..gap_end);
+            }
+            gap_start = gap_start.max(span.end);
+            if gap_start >= bounds.end {
+                return None;
+            }
+        }
+        (bounds.end - gap_start >= len).then_some(gap_start..bounds.end)
+    }
+}
+
+impl FromIterator<Range<i64>> for RangeSet {
+    fn from_iter<I: IntoIterator<Item = Range<i64>>>(iter: I) -> Self {
+        let mut set = RangeSet::new();
+        iter.into_iter().for_each(|range| set.insert(range));
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spans(set: &RangeSet) -> Vec<Range<i64>> {
+        set.iter().collect()
+    }
+
+    #[test]
+    fn test_insert_merges() {
+        let mut set = RangeSet::from_iter([0..2, 5..7, 10..12]);
+        assert_eq!(spans(&set), vec![0..2, 5..7, 10..12]);
+        set.insert(2..3);
+        assert_eq!(spans(&set), vec![0..3, 5..7, 10..12]);
+        set.insert(6..11);
+        assert_eq!(spans(&set), vec![0..3, 5..12]);
+        set.insert(-5..20);
+        assert_eq!(spans(&set), vec![-5..20]);
+        assert_eq!(set.covered(), 25);
+    }
+
+    #[test]
+    fn test_remove_splits() {
+        let mut set = RangeSet::from_iter([0..10, 20..30]);
+        set.remove(3..5);
+        assert_eq!(spans(&set), vec![0..3, 5..10, 20..30]);
+        set.remove(8..25);
+        assert_eq!(spans(&set), vec![0..3, 5..8, 25..30]);
+        set.remove(-1..100);
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_contains() {
+        let set = RangeSet::from_iter([0..2, 5..7]);
+        assert!(set.contains(0));
+        assert!(set.contains(6));
+        assert!(!set.contains(2));
+        assert!(!set.contains(-1));
+        assert_eq!(set.span_containing(1), Some(0..2));
+    }
+
+    #[test]
+    fn test_split_off() {
+        let mut lower = RangeSet::from_iter([0..10, 20..30]);
+        let upper = lower.split_off(5);
+        assert_eq!(spans(&lower), vec![0..5]);
+        assert_eq!(spans(&upper), vec![5..10, 20..30]);
+
+        let mut lower = RangeSet::from_iter([0..10, 20..30]);
+        let upper = lower.split_off(20);
+        assert_eq!(spans(&lower), vec![0..10]);
+        assert_eq!(spans(&upper), vec![20..30]);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut set = RangeSet::new();
+        set.insert(0..2);
+        set.merge(&RangeSet::from_iter([2..4, 8..9]));
+        assert_eq!(spans(&set), vec![0..4, 8..9]);
+    }
+
+    #[test]
+    fn test_first_fit() {
+        let set = RangeSet::from_iter([0..2, 5..9, 10..20]);
+        assert_eq!(set.first_fit(2), Some(0..2));
+        assert_eq!(set.first_fit(3), Some(5..9));
+        assert_eq!(set.first_fit(11), None);
+    }
+
+    #[test]
+    fn test_first_gap() {
+        let set = RangeSet::from_iter([0..2, 5..9, 10..20]);
+        assert_eq!(set.first_gap(1, 0..30), Some(2..5));
+        assert_eq!(set.first_gap(4, 0..30), Some(20..30));
+        assert_eq!(set.first_gap(4, 0..22), None);
+        assert_eq!(set.first_gap(1, 6..30), Some(9..10));
+        assert_eq!(set.first_gap(3, -5..30), Some(-5..0));
+    }
+}
@@ -1,2 +1,17 @@
+pub mod bitgrid;
+pub mod crypt;
+pub mod cycle;
 pub mod direction;
+pub mod example;
+pub mod explorer;
+pub mod input;
+pub mod interval;
+pub mod math;
+pub mod output;
 pub mod point;
+pub mod runner;
+pub mod strategy;
+pub mod trace;
+#[cfg(feature = "tui")]
+pub mod tui;
+pub mod union_find;
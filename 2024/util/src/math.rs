@@ -0,0 +1,223 @@
+//! Number theory over `i128`. Every operation is overflow-checked, so each function returns
+//! `None` on overflow as well as when no answer exists.
+
+pub fn gcd(a: i128, b: i128) -> Option<i128> {
+    extended_gcd(a, b).map(|(g, _, _)| g)
+}
+
+/// Returns `(g, x, y)` such that `a * x + b * y == g`, where `g` is the non-negative gcd.
+pub fn extended_gcd(a: i128, b: i128) -> Option<(i128, i128, i128)> {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1i128, 0i128);
+    let (mut old_y, mut y) = (0i128, 1i128);
+    while r != 0 {
+        let q = old_r.checked_div(r)?;
+        (old_r, r) = (r, old_r.checked_sub(q.checked_mul(r)?)?);
+        (old_x, x) = (x, old_x.checked_sub(q.checked_mul(x)?)?);
+        (old_y, y) = (y, old_y.checked_sub(q.checked_mul(y)?)?);
+    }
+    if old_r < 0 {
+        return Some((old_r.checked_neg()?, old_x.checked_neg()?, old_y.checked_neg()?));
+    }
+    Some((old_r, old_x, old_y))
+}
+
+pub fn lcm(a: i128, b: i128) -> Option<i128> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    let g = gcd(a, b)?;
+    (a / g).checked_mul(b)?.checked_abs()
+}
+
+/// Lowest common multiple of every value, or 1 for an empty iterator.
+pub fn lcm_all<I: IntoIterator<Item = i128>>(values: I) -> Option<i128> {
+    values.into_iter().try_fold(1, lcm)
+}
+
+/// The `x` in `0..modulus` with `a * x ≡ 1 (mod modulus)`, if `a` and `modulus` are coprime.
+pub fn mod_inverse(a: i128, modulus: i128) -> Option<i128> {
+    if modulus <= 0 {
+        return None;
+    }
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus)?;
+    (g == 1).then(|| x.rem_euclid(modulus))
+}
+
+/// Chinese remainder theorem over `(residue, modulus)` pairs. The moduli need not be coprime.
+/// Returns `(residue, modulus)` describing every solution, with the residue in `0..modulus`.
+pub fn crt(congruences: &[(i128, i128)]) -> Option<(i128, i128)> {
+    congruences
+        .iter()
+        .try_fold((0, 1), |(r1, m1), &(r2, m2)| {
+            if m2 <= 0 {
+                return None;
+            }
+            let (g, p, _) = extended_gcd(m1, m2)?;
+            let diff = r2.checked_sub(r1)?;
+            if diff % g != 0 {
+                return None;
+            }
+            let step = m2 / g;
+            let k = (diff / g)
+                .rem_euclid(step)
+                .checked_mul(p.rem_euclid(step))?
+                .rem_euclid(step);
+            let modulus = m1.checked_mul(step)?;
+            let residue = r1.checked_add(m1.checked_mul(k)?)?.rem_euclid(modulus);
+            Some((residue, modulus))
+        })
+}
+
+/// Every integer solution of `a * x + b * y == c`, as `(x + k * step_x, y + k * step_y)`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct LinearSolutions {
+    pub x: i128,
+    pub y: i128,
+    pub step_x: i128,
+    pub step_y: i128,
+}
+
+impl LinearSolutions {
+    pub fn at(&self, k: i128) -> Option<(i128, i128)> {
+        let x = self.x.checked_add(k.checked_mul(self.step_x)?)?;
+        let y = self.y.checked_add(k.checked_mul(self.step_y)?)?;
+        Some((x, y))
+    }
+
+    /// The inclusive range of `k` for which both `x` and `y` are non-negative, if any.
+    pub fn non_negative(&self) -> Option<(i128, i128)> {
+        let mut low = i128::MIN;
+        let mut high = i128::MAX;
+        for (start, step) in [(self.x, self.step_x), (self.y, self.step_y)] {
+            match step.signum() {
+                // start + k * step >= 0
+                1 => low = low.max(div_ceil(start.checked_neg()?, step)?),
+                -1 => high = high.min(div_floor(start, step.checked_neg()?)?),
+                _ if start < 0 => return None,
+                _ => (),
+            }
+        }
+        (low <= high).then_some((low, high))
+    }
+}
+
+/// Solves `a * x + b * y == c` over the integers.
+pub fn linear_diophantine(a: i128, b: i128, c: i128) -> Option<LinearSolutions> {
+    if a == 0 && b == 0 {
+        return (c == 0).then_some(LinearSolutions {
+            x: 0,
+            y: 0,
+            step_x: 1,
+            step_y: 0,
+        });
+    }
+    let (g, x, y) = extended_gcd(a, b)?;
+    if c % g != 0 {
+        return None;
+    }
+    let scale = c / g;
+    Some(LinearSolutions {
+        x: x.checked_mul(scale)?,
+        y: y.checked_mul(scale)?,
+        step_x: b / g,
+        step_y: (a / g).checked_neg()?,
+    })
+}
+
+/// The unique integer solution of `[[a, b], [c, d]] * [x, y] == [e, f]`. Returns `None` for a
+/// singular system or a solution that is not whole.
+pub fn solve_2x2([[a, b], [c, d]]: [[i128; 2]; 2], [e, f]: [i128; 2]) -> Option<(i128, i128)> {
+    let det = a.checked_mul(d)?.checked_sub(b.checked_mul(c)?)?;
+    if det == 0 {
+        return None;
+    }
+    let x_num = e.checked_mul(d)?.checked_sub(b.checked_mul(f)?)?;
+    let y_num = a.checked_mul(f)?.checked_sub(e.checked_mul(c)?)?;
+    if x_num % det != 0 || y_num % det != 0 {
+        return None;
+    }
+    Some((x_num / det, y_num / det))
+}
+
+fn div_floor(a: i128, b: i128) -> Option<i128> {
+    let q = a.checked_div(b)?;
+    if a % b != 0 && (a < 0) != (b < 0) {
+        return q.checked_sub(1);
+    }
+    Some(q)
+}
+
+fn div_ceil(a: i128, b: i128) -> Option<i128> {
+    let q = a.checked_div(b)?;
+    if a % b != 0 && (a < 0) == (b < 0) {
+        return q.checked_add(1);
+    }
+    Some(q)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extended_gcd() {
+        let (g, x, y) = extended_gcd(240, 46).unwrap();
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+
+        let (g, x, y) = extended_gcd(-12, 18).unwrap();
+        assert_eq!(g, 6);
+        assert_eq!(-12 * x + 18 * y, 6);
+
+        assert_eq!(gcd(0, 7), Some(7));
+        assert_eq!(gcd(i128::MIN, 0), None);
+    }
+
+    #[test]
+    fn test_lcm() {
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm_all([101, 103]), Some(10403));
+        assert_eq!(lcm_all([2, 3, 4, 5, 6]), Some(60));
+        assert_eq!(lcm_all([]), Some(1));
+        assert_eq!(lcm_all([i128::MAX, i128::MAX - 1]), None);
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(13, 101), (79, 103)]), Some((7083, 10403)));
+        // Non-coprime moduli
+        assert_eq!(crt(&[(3, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+    }
+
+    #[test]
+    fn test_linear_diophantine() {
+        let solutions = linear_diophantine(6, 4, 10).unwrap();
+        for k in -3..3 {
+            let (x, y) = solutions.at(k).unwrap();
+            assert_eq!(6 * x + 4 * y, 10);
+        }
+        assert_eq!(linear_diophantine(6, 4, 9), None);
+
+        let (low, high) = solutions.non_negative().unwrap();
+        assert_eq!(low, high);
+        assert_eq!(solutions.at(low), Some((1, 1)));
+    }
+
+    #[test]
+    fn test_solve_2x2() {
+        assert_eq!(solve_2x2([[94, 22], [34, 67]], [8400, 5400]), Some((80, 40)));
+        assert_eq!(solve_2x2([[26, 67], [66, 21]], [12748, 12176]), None);
+        assert_eq!(solve_2x2([[1, 2], [2, 4]], [3, 6]), None);
+    }
+}
@@ -0,0 +1,202 @@
+use std::{fmt::Debug, str::FromStr, time::Duration};
+
+/// How `Runner` writes its results to stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Human,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FormatErr {
+    UnknownFormat(String),
+}
+
+impl FromStr for Format {
+    type Err = FormatErr;
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "human" => Ok(Format::Human),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(FormatErr::UnknownFormat(format.to_string())),
+        }
+    }
+}
+
+/// A puzzle answer as it should be reported, without the `Some(..)` or `Ok(..)` around it.
+pub trait Answer {
+    fn render(&self) -> String;
+}
+
+macro_rules! display_answer {
+    ($($t:ty),*) => {
+        $(impl Answer for $t {
+            fn render(&self) -> String {
+                self.to_string()
+            }
+        })*
+    };
+}
+
+display_answer!(i32, i64, i128, u32, u64, u128, usize, String, &str);
+
+impl<A: Answer, B: Answer> Answer for (A, B) {
+    fn render(&self) -> String {
+        format!("{},{}", self.0.render(), self.1.render())
+    }
+}
+
+impl<T: Answer> Answer for Option<T> {
+    fn render(&self) -> String {
+        match self {
+            Some(answer) => answer.render(),
+            None => "none".to_string(),
+        }
+    }
+}
+
+impl<T: Answer, E: Debug> Answer for Result<T, E> {
+    fn render(&self) -> String {
+        match self {
+            Ok(answer) => answer.render(),
+            Err(err) => format!("error: {:?}", err),
+        }
+    }
+}
+
+/// One line of output. A record for a whole part names the strategy that produced it and how
+/// long it took; a detail record names an item within the part instead, such as one machine or
+/// one design, and has neither.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub day: String,
+    pub part: &'static str,
+    pub strategy: Option<&'static str>,
+    pub item: Option<String>,
+    pub answer: String,
+    pub elapsed: Option<Duration>,
+}
+
+impl Record {
+    pub const CSV_HEADER: &'static str = "day,part,strategy,item,answer,elapsed_us";
+
+    fn elapsed_us(&self) -> Option<u128> {
+        self.elapsed.map(|e| e.as_micros())
+    }
+
+    pub fn to_json(&self) -> String {
+        let string = |s: Option<&str>| s.map_or("null".to_string(), json_string);
+        format!(
+            "{{\"day\":{},\"part\":{},\"strategy\":{},\"item\":{},\"answer\":{},\"elapsed_us\":{}}}",
+            json_string(&self.day),
+            json_string(self.part),
+            string(self.strategy),
+            string(self.item.as_deref()),
+            json_string(&self.answer),
+            self.elapsed_us().map_or("null".to_string(), |us| us.to_string())
+        )
+    }
+
+    pub fn to_csv(&self) -> String {
+        [
+            csv_field(&self.day),
+            csv_field(self.part),
+            csv_field(self.strategy.unwrap_or_default()),
+            csv_field(self.item.as_deref().unwrap_or_default()),
+            csv_field(&self.answer),
+            self.elapsed_us().map(|us| us.to_string()).unwrap_or_default(),
+        ]
+        .join(",")
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        return format!("\"{}\"", s.replace('"', "\"\""));
+    }
+    s.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> Record {
+        Record {
+            day: "day18".to_string(),
+            part: "B",
+            strategy: Some("default"),
+            item: None,
+            answer: Some((50usize, 28usize)).render(),
+            elapsed: Some(Duration::from_micros(1500)),
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(Some(4usize).render(), "4");
+        assert_eq!(None::<usize>.render(), "none");
+        assert_eq!(Ok::<_, ()>("a,b".to_string()).render(), "a,b");
+        assert_eq!(Err::<usize, _>("bad").render(), "error: \"bad\"");
+    }
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!("csv".parse(), Ok(Format::Csv));
+        assert_eq!(
+            "xml".parse::<Format>(),
+            Err(FormatErr::UnknownFormat("xml".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_to_json() {
+        assert_eq!(
+            record().to_json(),
+            r#"{"day":"day18","part":"B","strategy":"default","item":null,"answer":"50,28","elapsed_us":1500}"#
+        );
+        let detail = Record {
+            strategy: None,
+            item: Some("say \"hi\"\n".to_string()),
+            elapsed: None,
+            ..record()
+        };
+        assert_eq!(
+            detail.to_json(),
+            r#"{"day":"day18","part":"B","strategy":null,"item":"say \"hi\"\n","answer":"50,28","elapsed_us":null}"#
+        );
+    }
+
+    #[test]
+    fn test_to_csv() {
+        assert_eq!(record().to_csv(), "day18,B,default,,\"50,28\",1500");
+        let detail = Record {
+            strategy: None,
+            item: Some("say \"hi\"".to_string()),
+            elapsed: None,
+            ..record()
+        };
+        assert_eq!(detail.to_csv(), "day18,B,,\"say \"\"hi\"\"\",\"50,28\",");
+    }
+}
@@ -0,0 +1,338 @@
+use std::{
+    cell::Cell,
+    fmt::Debug,
+    io::{self, BufRead},
+    path::Path,
+};
+
+use tracing::debug;
+
+use crate::{
+    input,
+    output::{Answer, Format, FormatErr, Record},
+    strategy::{self, Outcome, Selection, StrategyErr, Strategies},
+    trace,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum RunnerErr {
+    MissingValue(String),
+    UnrecognisedArgument(String),
+    BadFormat(FormatErr),
+}
+
+/// Command-line options shared by every day's binary.
+///
+/// `--strategy <name>` runs one named strategy per part, and `--strategy all` runs every
+/// strategy not marked as slow, checks that they agree and reports the fastest. `-v` and `-vv`
+/// turn on debug and trace events from the solvers. `--format json|csv` writes one record per
+/// answer instead of the human-readable lines, and `--details` adds per-item rows where a day
+/// provides them. `--explore <part>` asks a day with a simulation to open it in the explorer
+/// instead of solving, and `--export <format>` asks a day that can write its puzzle in another
+/// format to print it instead. A bare argument is the input to read instead of the day's own,
+/// with `-` meaning stdin.
+#[derive(Debug, Default, PartialEq)]
+pub struct Runner {
+    selection: Selection,
+    verbosity: u8,
+    format: Format,
+    details: bool,
+    explore: Option<String>,
+    export: Option<String>,
+    input: Option<String>,
+    day: String,
+    header_written: Cell<bool>,
+}
+
+impl Runner {
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Runner, RunnerErr> {
+        let mut runner = Runner::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--strategy" | "-s" => {
+                    let name = args.next().ok_or(RunnerErr::MissingValue(arg))?;
+                    runner.selection = match name.as_str() {
+                        "all" => Selection::All,
+                        _ => Selection::Named(name),
+                    };
+                }
+                "--format" | "-f" => {
+                    let format = args.next().ok_or(RunnerErr::MissingValue(arg))?;
+                    runner.format = format.parse().map_err(RunnerErr::BadFormat)?;
+                }
+                "--details" => runner.details = true,
+                "--explore" | "-e" => {
+                    runner.explore = Some(args.next().ok_or(RunnerErr::MissingValue(arg))?);
+                }
+                "--export" => {
+                    runner.export = Some(args.next().ok_or(RunnerErr::MissingValue(arg))?);
+                }
+                "--verbose" => runner.verbosity += 1,
+                "-v" | "-vv" | "-vvv" => runner.verbosity += (arg.len() - 1) as u8,
+                _ if runner.input.is_none() && (arg == input::STDIN || !arg.starts_with('-')) => {
+                    runner.input = Some(arg);
+                }
+                _ => return Err(RunnerErr::UnrecognisedArgument(arg)),
+            }
+        }
+        Ok(runner)
+    }
+
+    /// Parses the process arguments and sets up tracing to match. The day in each record is
+    /// taken from the binary's name.
+    pub fn from_args() -> Runner {
+        let mut args = std::env::args();
+        let program = args.next().unwrap_or_default();
+        let mut runner = Runner::parse(args).unwrap_or_else(|err| {
+            eprintln!("{:?}", err);
+            eprintln!(
+                "Usage: [--strategy <name|all>] [--format <human|json|csv>] [--details] \
+                 [--explore <part>] [--export <format>] [-v|-vv] [input|-]"
+            );
+            std::process::exit(2);
+        });
+        runner.day = Path::new(&program)
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        trace::init(runner.verbosity);
+        runner
+    }
+
+    pub fn with_day(mut self, day: &str) -> Runner {
+        self.day = day.to_string();
+        self
+    }
+
+    pub fn selection(&self) -> &Selection {
+        &self.selection
+    }
+
+    pub fn verbosity(&self) -> u8 {
+        self.verbosity
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// The part whose simulation should be explored, if `--explore` was given.
+    pub fn explore(&self) -> Option<&str> {
+        self.explore.as_deref()
+    }
+
+    /// The format to write the puzzle in, if `--export` was given.
+    pub fn export(&self) -> Option<&str> {
+        self.export.as_deref()
+    }
+
+    /// Opens the input named on the command line, or `default` if there was none.
+    pub fn open_input(&self, default: &str) -> io::Result<Box<dyn BufRead>> {
+        input::open(self.input.as_deref().unwrap_or(default))
+    }
+
+    /// Reads the whole of the input, as `open_input` would find it.
+    pub fn read_input(&self, default: &str) -> io::Result<String> {
+        input::read_to_string(self.open_input(default)?)
+    }
+
+    /// The lines to print for a record. The CSV header goes before the first record only.
+    fn lines(&self, record: &Record) -> Vec<String> {
+        match self.format {
+            Format::Human => match &record.item {
+                Some(item) => vec![format!("  {}: {}", item, record.answer)],
+                None => vec![format!("Part {}: {}", record.part, record.answer)],
+            },
+            Format::Json => vec![record.to_json()],
+            Format::Csv if self.header_written.replace(true) => vec![record.to_csv()],
+            Format::Csv => vec![Record::CSV_HEADER.to_string(), record.to_csv()],
+        }
+    }
+
+    fn emit(&self, record: &Record) {
+        self.lines(record).iter().for_each(|line| println!("{}", line));
+    }
+
+    /// Runs and prints one part. A named strategy that this part does not have falls back to
+    /// the default, since most days only have alternatives for one of their parts.
+    pub fn run<T: Answer + Debug + PartialEq>(&self, strategies: &Strategies<T>) -> Vec<Outcome<T>> {
+        let selection = match &self.selection {
+            Selection::Named(name) if !strategies.contains(name) => {
+                eprintln!(
+                    "Part {} has no strategy {:?} (has {:?}), using the default",
+                    strategies.part(),
+                    name,
+                    strategies.names()
+                );
+                &Selection::Default
+            }
+            selection => selection,
+        };
+
+        let outcomes = match strategies.run(selection) {
+            Ok(outcomes) => outcomes,
+            Err(StrategyErr::Disagreement(answers)) => {
+                panic!("Part {} strategies disagree: {:?}", strategies.part(), answers)
+            }
+            Err(err) => panic!("Could not run part {}: {:?}", strategies.part(), err),
+        };
+
+        for outcome in &outcomes {
+            debug!(
+                part = strategies.part(),
+                strategy = outcome.strategy,
+                elapsed = ?outcome.elapsed,
+                "solved"
+            );
+        }
+
+        let record = |outcome: &Outcome<T>| Record {
+            day: self.day.clone(),
+            part: strategies.part(),
+            strategy: Some(outcome.strategy),
+            item: None,
+            answer: outcome.answer.render(),
+            elapsed: Some(outcome.elapsed),
+        };
+        if self.format != Format::Human {
+            outcomes.iter().for_each(|outcome| self.emit(&record(outcome)));
+            return outcomes;
+        }
+
+        self.emit(&record(&outcomes[0]));
+        if *selection == Selection::All {
+            for outcome in &outcomes {
+                println!("  {:<12} {:?}", outcome.strategy, outcome.elapsed);
+            }
+            for name in strategies.slow_names() {
+                println!("  {:<12} skipped (slow)", name);
+            }
+            if let Some(fastest) = strategy::fastest(&outcomes) {
+                println!("  fastest: {}", fastest.strategy);
+            }
+        }
+        outcomes
+    }
+
+    /// Runs a part that only has one implementation, registered as "default".
+    pub fn solve<T, F>(&self, part: &'static str, solve: F) -> Vec<Outcome<T>>
+    where
+        T: Answer + Debug + PartialEq,
+        F: Fn() -> T,
+    {
+        self.run(&Strategies::new(part).register("default", solve))
+    }
+
+    /// Prints per-item rows for a part, e.g. the cost of each machine. Nothing is computed
+    /// unless `--details` was given.
+    pub fn details<F, I, T>(&self, part: &'static str, rows: F)
+    where
+        F: FnOnce() -> I,
+        I: IntoIterator<Item = (String, T)>,
+        T: Answer,
+    {
+        if !self.details {
+            return;
+        }
+        for (item, answer) in rows() {
+            self.emit(&Record {
+                day: self.day.clone(),
+                part,
+                strategy: None,
+                item: Some(item),
+                answer: answer.render(),
+                elapsed: None,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Runner::parse(args(&[])).unwrap().selection, Selection::Default);
+        assert_eq!(
+            Runner::parse(args(&["--strategy", "junction"])).unwrap().selection,
+            Selection::Named("junction".to_string())
+        );
+        assert_eq!(
+            Runner::parse(args(&["-s", "all"])).unwrap().selection,
+            Selection::All
+        );
+        assert_eq!(Runner::parse(args(&["-v"])).unwrap().verbosity, 1);
+        assert_eq!(Runner::parse(args(&["-vv"])).unwrap().verbosity, 2);
+        assert_eq!(Runner::parse(args(&["-v", "--verbose"])).unwrap().verbosity, 2);
+        assert_eq!(
+            Runner::parse(args(&["--strategy"])),
+            Err(RunnerErr::MissingValue("--strategy".to_string()))
+        );
+        let runner = Runner::parse(args(&["--format", "json", "--details"])).unwrap();
+        assert_eq!(runner.format, Format::Json);
+        assert!(runner.details);
+        let runner = Runner::parse(args(&["--explore", "B"])).unwrap();
+        assert_eq!(runner.explore(), Some("B"));
+        let runner = Runner::parse(args(&["--export", "json"])).unwrap();
+        assert_eq!(runner.export(), Some("json"));
+        let runner = Runner::parse(args(&["big.txt", "-v"])).unwrap();
+        assert_eq!(runner.input.as_deref(), Some("big.txt"));
+        assert_eq!(Runner::parse(args(&["-"])).unwrap().input.as_deref(), Some("-"));
+        assert_eq!(
+            Runner::parse(args(&["a.txt", "b.txt"])),
+            Err(RunnerErr::UnrecognisedArgument("b.txt".to_string()))
+        );
+        assert_eq!(
+            Runner::parse(args(&["-f", "yaml"])),
+            Err(RunnerErr::BadFormat(FormatErr::UnknownFormat(
+                "yaml".to_string()
+            )))
+        );
+        assert_eq!(
+            Runner::parse(args(&["--fast"])),
+            Err(RunnerErr::UnrecognisedArgument("--fast".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_unknown_name_falls_back() {
+        let runner = Runner::parse(args(&["--strategy", "junction"])).unwrap();
+        let strategies = Strategies::new("B").register("graph", || 1);
+        let outcomes = runner.run(&strategies);
+        assert_eq!(outcomes[0].strategy, "graph");
+    }
+
+    #[test]
+    fn test_lines() {
+        let record = Record {
+            day: "day13".to_string(),
+            part: "A",
+            strategy: Some("default"),
+            item: None,
+            answer: "480".to_string(),
+            elapsed: None,
+        };
+        let detail = Record {
+            item: Some("machine 1".to_string()),
+            strategy: None,
+            ..record.clone()
+        };
+
+        let runner = Runner::default();
+        assert_eq!(runner.lines(&record), vec!["Part A: 480"]);
+        assert_eq!(runner.lines(&detail), vec!["  machine 1: 480"]);
+
+        let runner = Runner::parse(args(&["--format", "csv"])).unwrap();
+        assert_eq!(
+            runner.lines(&record),
+            vec![Record::CSV_HEADER, "day13,A,default,,480,"]
+        );
+        assert_eq!(runner.lines(&detail), vec!["day13,A,,machine 1,480,"]);
+    }
+}
@@ -0,0 +1,221 @@
+use std::{
+    fmt::Debug,
+    time::{Duration, Instant},
+};
+
+/// Which registered strategies to run for a part.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Selection {
+    #[default]
+    Default,
+    Named(String),
+    All,
+}
+
+/// The answer one strategy gave, and how long it took.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outcome<T> {
+    pub strategy: &'static str,
+    pub answer: T,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum StrategyErr {
+    NoStrategies,
+    UnknownStrategy(String),
+    Disagreement(Vec<(&'static str, String)>),
+}
+
+type Solver<'a, T> = Box<dyn Fn() -> T + 'a>;
+
+struct Entry<'a, T> {
+    name: &'static str,
+    solve: Solver<'a, T>,
+    slow: bool,
+}
+
+/// Competing implementations of the same part, by name. The first one registered is the
+/// default. Slow strategies only run when asked for by name or through `cross_check`, so that
+/// `Selection::All` stays usable on real inputs.
+pub struct Strategies<'a, T> {
+    part: &'static str,
+    entries: Vec<Entry<'a, T>>,
+}
+
+impl<'a, T: Debug + PartialEq> Strategies<'a, T> {
+    pub fn new(part: &'static str) -> Strategies<'a, T> {
+        Strategies {
+            part,
+            entries: vec![],
+        }
+    }
+
+    pub fn register<F>(mut self, name: &'static str, solve: F) -> Strategies<'a, T>
+    where
+        F: Fn() -> T + 'a,
+    {
+        self.entries.push(Entry {
+            name,
+            solve: Box::new(solve),
+            slow: false,
+        });
+        self
+    }
+
+    pub fn register_slow<F>(mut self, name: &'static str, solve: F) -> Strategies<'a, T>
+    where
+        F: Fn() -> T + 'a,
+    {
+        self.entries.push(Entry {
+            name,
+            solve: Box::new(solve),
+            slow: true,
+        });
+        self
+    }
+
+    pub fn part(&self) -> &'static str {
+        self.part
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.entries.iter().map(|e| e.name).collect()
+    }
+
+    pub fn slow_names(&self) -> Vec<&'static str> {
+        self.entries.iter().filter(|e| e.slow).map(|e| e.name).collect()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.iter().any(|e| e.name == name)
+    }
+
+    fn time(entry: &Entry<'a, T>) -> Outcome<T> {
+        let start = Instant::now();
+        let answer = (entry.solve)();
+        Outcome {
+            strategy: entry.name,
+            answer,
+            elapsed: start.elapsed(),
+        }
+    }
+
+    fn run_agreeing(&self, chosen: Vec<&Entry<'a, T>>) -> Result<Vec<Outcome<T>>, StrategyErr> {
+        if chosen.is_empty() {
+            return Err(StrategyErr::NoStrategies);
+        }
+
+        let outcomes: Vec<_> = chosen.into_iter().map(Strategies::time).collect();
+        if outcomes.iter().any(|o| o.answer != outcomes[0].answer) {
+            let answers = outcomes
+                .iter()
+                .map(|o| (o.strategy, format!("{:?}", o.answer)))
+                .collect();
+            return Err(StrategyErr::Disagreement(answers));
+        }
+        Ok(outcomes)
+    }
+
+    /// Runs the selected strategies. With `Selection::All`, every answer must match or a
+    /// `Disagreement` listing each strategy's answer is returned.
+    pub fn run(&self, selection: &Selection) -> Result<Vec<Outcome<T>>, StrategyErr> {
+        let chosen = match selection {
+            Selection::Default => self.entries.iter().take(1).collect(),
+            Selection::Named(name) => {
+                let entry = self.entries.iter().find(|e| e.name == name);
+                vec![entry.ok_or_else(|| StrategyErr::UnknownStrategy(name.clone()))?]
+            }
+            Selection::All => self.entries.iter().filter(|e| !e.slow).collect(),
+        };
+        self.run_agreeing(chosen)
+    }
+
+    /// Runs the default strategy alone and returns its answer.
+    pub fn run_default(&self) -> Result<T, StrategyErr> {
+        let mut outcomes = self.run(&Selection::Default)?;
+        Ok(outcomes.remove(0).answer)
+    }
+
+    /// Runs every strategy, slow ones included, and checks that they agree.
+    pub fn cross_check(&self) -> Result<Vec<Outcome<T>>, StrategyErr> {
+        self.run_agreeing(self.entries.iter().collect())
+    }
+}
+
+/// The quickest of a set of outcomes.
+pub fn fastest<T>(outcomes: &[Outcome<T>]) -> Option<&Outcome<T>> {
+    outcomes.iter().min_by_key(|o| o.elapsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strategies() -> Strategies<'static, usize> {
+        Strategies::new("A")
+            .register("fast", || 4)
+            .register("slow", || {
+                std::thread::sleep(Duration::from_millis(5));
+                4
+            })
+    }
+
+    #[test]
+    fn test_default_is_first() {
+        let outcomes = strategies().run(&Selection::Default).unwrap();
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].strategy, "fast");
+        assert_eq!(outcomes[0].answer, 4);
+        assert_eq!(strategies().run_default(), Ok(4));
+    }
+
+    #[test]
+    fn test_named() {
+        let outcomes = strategies()
+            .run(&Selection::Named("slow".to_string()))
+            .unwrap();
+        assert_eq!(outcomes[0].strategy, "slow");
+
+        let err = strategies().run(&Selection::Named("nope".to_string()));
+        assert_eq!(err, Err(StrategyErr::UnknownStrategy("nope".to_string())));
+    }
+
+    #[test]
+    fn test_all_agree() {
+        let outcomes = strategies().run(&Selection::All).unwrap();
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(fastest(&outcomes).unwrap().strategy, "fast");
+    }
+
+    #[test]
+    fn test_all_disagree() {
+        let result = strategies().register("wrong", || 5).run(&Selection::All);
+        let expected = vec![
+            ("fast", "4".to_string()),
+            ("slow", "4".to_string()),
+            ("wrong", "5".to_string()),
+        ];
+        assert_eq!(result, Err(StrategyErr::Disagreement(expected)));
+    }
+
+    #[test]
+    fn test_slow_skipped_by_all() {
+        let strategies = strategies().register_slow("brute", || 4);
+        let outcomes = strategies.run(&Selection::All).unwrap();
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(strategies.slow_names(), vec!["brute"]);
+
+        let outcomes = strategies
+            .run(&Selection::Named("brute".to_string()))
+            .unwrap();
+        assert_eq!(outcomes[0].strategy, "brute");
+        assert_eq!(strategies.cross_check().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_no_strategies() {
+        let empty: Strategies<usize> = Strategies::new("B");
+        assert_eq!(empty.run(&Selection::All), Err(StrategyErr::NoStrategies));
+    }
+}
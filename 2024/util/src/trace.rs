@@ -0,0 +1,40 @@
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::EnvFilter;
+
+/// Overrides `-v` with a full filter, e.g. `AOC_LOG=day16=trace` or
+/// `AOC_LOG=day15::warehouse=debug`. Events default to their module path as target, so each
+/// day's crate name works as a per-day switch.
+pub const FILTER_VAR: &str = "AOC_LOG";
+
+pub fn level_for(verbosity: u8) -> LevelFilter {
+    match verbosity {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
+}
+
+/// Sends events to stderr, so answers on stdout are unaffected. Safe to call more than once;
+/// only the first call installs a subscriber.
+pub fn init(verbosity: u8) {
+    let filter = EnvFilter::try_from_env(FILTER_VAR)
+        .unwrap_or_else(|_| EnvFilter::new(level_for(verbosity).to_string()));
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .without_time()
+        .try_init();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_for() {
+        assert_eq!(level_for(0), LevelFilter::WARN);
+        assert_eq!(level_for(1), LevelFilter::DEBUG);
+        assert_eq!(level_for(2), LevelFilter::TRACE);
+        assert_eq!(level_for(7), LevelFilter::TRACE);
+    }
+}
@@ -0,0 +1,164 @@
+//! Terminal front end for an `Explorer`.
+//!
+//! Keys: `l`/`→` and `h`/`←` step forward and back, `L`/`PgDn` and `H`/`PgUp` jump a hundred
+//! steps, and `g` goes to a step. Any of these take a count typed first, so `20l` steps forward
+//! twenty and `7083g` goes to step 7083. `:` opens a prompt for any `Command`, such as
+//! `:until density 0.3` or `:mark 4 7`. `q` quits.
+
+use std::io::{self, Stdout, Write};
+
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{read, Event, KeyCode, KeyEventKind},
+    execute, queue,
+    style::{Attribute, Print, SetAttribute},
+    terminal::{
+        disable_raw_mode, enable_raw_mode, size, Clear, ClearType, EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
+};
+
+use crate::{
+    explorer::{Command, Explorer, Simulation},
+    point::Point,
+};
+
+const JUMP: usize = 100;
+const HISTORY_SHOWN: usize = 12;
+const HELP: &str = "h/l step  H/L jump  <n>g goto  :command  q quit";
+
+pub fn explore<S: Simulation>(mut explorer: Explorer<S>) -> io::Result<()> {
+    let mut out = io::stdout();
+    enable_raw_mode()?;
+    execute!(out, EnterAlternateScreen, Hide)?;
+    let result = event_loop(&mut explorer, &mut out);
+    execute!(out, Show, LeaveAlternateScreen)?;
+    disable_raw_mode()?;
+    result
+}
+
+fn event_loop<S: Simulation>(explorer: &mut Explorer<S>, out: &mut Stdout) -> io::Result<()> {
+    let mut count = String::new();
+    let mut prompt: Option<String> = None;
+    let mut message = HELP.to_string();
+    loop {
+        let bottom_line = match &prompt {
+            Some(line) => format!(":{}", line),
+            None if !count.is_empty() => count.clone(),
+            None => message.clone(),
+        };
+        draw(explorer, out, &bottom_line)?;
+
+        let Event::Key(key) = read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        if let Some(line) = prompt.as_mut() {
+            match key.code {
+                KeyCode::Enter => {
+                    let result = line.parse::<Command>().map(|c| explorer.apply(&c));
+                    message = match result {
+                        Ok(Ok(())) => HELP.to_string(),
+                        Ok(Err(err)) => format!("{:?}", err),
+                        Err(err) => format!("{:?}", err),
+                    };
+                    prompt = None;
+                }
+                KeyCode::Esc => prompt = None,
+                KeyCode::Backspace => _ = line.pop(),
+                KeyCode::Char(c) => line.push(c),
+                _ => (),
+            }
+            continue;
+        }
+
+        let n = count.parse::<usize>().ok();
+        let command = match key.code {
+            KeyCode::Char(c) if c.is_ascii_digit() => {
+                count.push(c);
+                continue;
+            }
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char(':') => {
+                prompt = Some(String::new());
+                continue;
+            }
+            KeyCode::Right | KeyCode::Char('l') => Command::Forward(n.unwrap_or(1)),
+            KeyCode::Left | KeyCode::Char('h') => Command::Back(n.unwrap_or(1)),
+            KeyCode::PageDown | KeyCode::Char('L') => Command::Forward(n.unwrap_or(1) * JUMP),
+            KeyCode::PageUp | KeyCode::Char('H') => Command::Back(n.unwrap_or(1) * JUMP),
+            KeyCode::Char('g') => Command::Goto(n.unwrap_or(0)),
+            _ => {
+                count.clear();
+                continue;
+            }
+        };
+        count.clear();
+        message = match explorer.apply(&command) {
+            Ok(()) => HELP.to_string(),
+            Err(err) => format!("{:?}", err),
+        };
+    }
+}
+
+fn status<S: Simulation>(explorer: &Explorer<S>) -> String {
+    let mut line = format!("step {}", explorer.step());
+    if let Some(end) = explorer.end() {
+        line.push_str(&format!(" of {}", end));
+    }
+    for (name, value) in explorer.metrics() {
+        line.push_str(&format!("  {} {:.3}", name, value));
+    }
+    line
+}
+
+fn history<S: Simulation>(explorer: &mut Explorer<S>) -> String {
+    let Some(point) = explorer.marked() else {
+        return String::new();
+    };
+    let changes = explorer.history();
+    let shown: Vec<_> = changes
+        .iter()
+        .skip(changes.len().saturating_sub(HISTORY_SHOWN))
+        .map(|(step, cell)| format!("{}:{:?}", step, cell))
+        .collect();
+    format!("mark ({}, {}): {}", point.0, point.1, shown.join(" "))
+}
+
+fn draw<S: Simulation>(
+    explorer: &mut Explorer<S>,
+    out: &mut Stdout,
+    bottom_line: &str,
+) -> io::Result<()> {
+    let (cols, rows) = size()?;
+    let (cols, rows) = (cols as usize, rows as usize);
+    queue!(out, Clear(ClearType::All))?;
+
+    let frame = explorer.frame();
+    for (row_idx, row) in frame.rows().iter().take(rows.saturating_sub(3)).enumerate() {
+        queue!(out, MoveTo(0, row_idx as u16))?;
+        for (col_idx, cell) in row.iter().take(cols).enumerate() {
+            if explorer.marked() == Some(Point(row_idx as i32, col_idx as i32)) {
+                queue!(
+                    out,
+                    SetAttribute(Attribute::Reverse),
+                    Print(cell),
+                    SetAttribute(Attribute::Reset)
+                )?;
+            } else {
+                queue!(out, Print(cell))?;
+            }
+        }
+    }
+
+    let lines = [status(explorer), history(explorer), bottom_line.to_string()];
+    for (idx, line) in lines.iter().enumerate() {
+        let row = rows.saturating_sub(3) + idx;
+        let line: String = line.chars().take(cols).collect();
+        queue!(out, MoveTo(0, row as u16), Print(line))?;
+    }
+    out.flush()
+}
@@ -0,0 +1,216 @@
+use std::{collections::HashMap, hash::Hash};
+
+use crate::{direction::Direction, point::Point};
+
+/// Disjoint-set forest over the indices `0..len`, with path compression and union by rank.
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    components: usize,
+}
+
+impl UnionFind {
+    pub fn new(len: usize) -> UnionFind {
+        UnionFind {
+            parent: (0..len).collect(),
+            rank: vec![0; len],
+            components: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut x = x;
+        while self.parent[x] != root {
+            let next = self.parent[x];
+            self.parent[x] = root;
+            x = next;
+        }
+        root
+    }
+
+    /// Merges the sets holding `a` and `b`. Returns false if they were already joined.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let a = self.find(a);
+        let b = self.find(b);
+        if a == b {
+            return false;
+        }
+        match self.rank[a].cmp(&self.rank[b]) {
+            std::cmp::Ordering::Less => self.parent[a] = b,
+            std::cmp::Ordering::Greater => self.parent[b] = a,
+            std::cmp::Ordering::Equal => {
+                self.parent[b] = a;
+                self.rank[a] += 1;
+            }
+        }
+        self.components -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Groups every index by its set, in order of each set's smallest index.
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        let mut group_of_root = HashMap::new();
+        let mut groups: Vec<Vec<usize>> = vec![];
+        for x in 0..self.len() {
+            let root = self.find(x);
+            let group = *group_of_root.entry(root).or_insert_with(|| {
+                groups.push(vec![]);
+                groups.len() - 1
+            });
+            groups[group].push(x);
+        }
+        groups
+    }
+}
+
+/// Splits a row-major grid into 4-connected regions, where neighbours are joined if `joined`
+/// returns true for their values. Regions are ordered by their first cell in reading order.
+pub fn grid_components<T, F>(grid: &[Vec<T>], joined: F) -> Vec<Vec<Point>>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    let width = grid.iter().map(|row| row.len()).max().unwrap_or(0);
+    let index = |p: &Point| p.0 as usize * width + p.1 as usize;
+    let value = |p: &Point| {
+        if p.0 < 0 || p.1 < 0 {
+            return None;
+        }
+        grid.get(p.0 as usize)?.get(p.1 as usize)
+    };
+
+    let mut sets = UnionFind::new(grid.len() * width);
+    for (row_idx, row) in grid.iter().enumerate() {
+        for (col_idx, cell) in row.iter().enumerate() {
+            let point = Point::from((row_idx, col_idx));
+            for dir in [Direction::Right, Direction::Down] {
+                let neighbour = point.add(&dir.to_point());
+                if value(&neighbour).is_some_and(|other| joined(cell, other)) {
+                    sets.union(index(&point), index(&neighbour));
+                }
+            }
+        }
+    }
+
+    sets.groups()
+        .into_iter()
+        .map(|group| {
+            group
+                .into_iter()
+                .map(|idx| Point::from((idx / width, idx % width)))
+                .filter(|p| value(p).is_some())
+                .collect::<Vec<_>>()
+        })
+        .filter(|group| !group.is_empty())
+        .collect()
+}
+
+/// Connected components of an undirected adjacency map. Nodes that only appear as neighbours are
+/// included too.
+pub fn components<K>(adjacency: &HashMap<K, Vec<K>>) -> Vec<Vec<K>>
+where
+    K: Hash + Eq + Clone,
+{
+    let mut nodes: Vec<K> = vec![];
+    let mut index: HashMap<K, usize> = HashMap::new();
+    let mut index_of = |node: &K| {
+        *index.entry(node.clone()).or_insert_with(|| {
+            nodes.push(node.clone());
+            nodes.len() - 1
+        })
+    };
+
+    let mut edges = vec![];
+    for (node, neighbours) in adjacency {
+        let node = index_of(node);
+        for neighbour in neighbours {
+            edges.push((node, index_of(neighbour)));
+        }
+    }
+
+    let mut sets = UnionFind::new(nodes.len());
+    for (a, b) in edges {
+        sets.union(a, b);
+    }
+    sets.groups()
+        .into_iter()
+        .map(|group| group.into_iter().map(|idx| nodes[idx].clone()).collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut sets = UnionFind::new(6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert!(sets.connected(0, 3));
+        assert!(!sets.connected(0, 4));
+        assert_eq!(sets.component_count(), 3);
+        assert_eq!(sets.groups(), vec![vec![0, 1, 2, 3], vec![4], vec![5]]);
+    }
+
+    #[test]
+    fn test_grid_components() {
+        let grid: Vec<Vec<char>> = ["AAB", "ABB", "CCB"]
+            .iter()
+            .map(|l| l.chars().collect())
+            .collect();
+        let regions = grid_components(&grid, |a, b| a == b);
+        assert_eq!(
+            regions,
+            vec![
+                vec![Point(0, 0), Point(0, 1), Point(1, 0)],
+                vec![Point(0, 2), Point(1, 1), Point(1, 2), Point(2, 2)],
+                vec![Point(2, 0), Point(2, 1)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_grid_components_ragged() {
+        let grid = vec![vec![1, 1, 1], vec![1]];
+        let regions = grid_components(&grid, |a, b| a == b);
+        assert_eq!(
+            regions,
+            vec![vec![Point(0, 0), Point(0, 1), Point(0, 2), Point(1, 0)]]
+        );
+    }
+
+    #[test]
+    fn test_components() {
+        let mut adjacency = HashMap::new();
+        adjacency.insert("a", vec!["b"]);
+        adjacency.insert("b", vec!["a", "c"]);
+        adjacency.insert("d", vec!["e"]);
+        let mut groups = components(&adjacency);
+        groups.iter_mut().for_each(|g| g.sort());
+        groups.sort();
+        assert_eq!(groups, vec![vec!["a", "b", "c"], vec!["d", "e"]]);
+    }
+}